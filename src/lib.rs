@@ -1,8 +1,61 @@
+//! Functional-style md5 hashing
+//!
+//! Everything is in the `md5_core` module. The core hashing (`Md5::calculate`, the
+//! incremental `Md5` object, `Md5Digest`, `HmacMd5`, `Md5::compress`...) only needs `core`, so
+//! the crate works in `no_std` when built with `default-features = false`
+//!
+//! # Features
+//!
+//! - `std` (default): everything that touches `std::io` or the file system, like
+//!   `Md5::from_reader`, `Md5::hash_file`, `Md5::hash_dir`, `HashingReader` and the
+//!   `std::io::Write` implementation, plus the `std::error::Error` implementations
+//! - `alloc` (enabled by `std`): everything that returns a `String` or a `Vec`, like
+//!   `Md5::calculate_hex`, `Md5::calculate_batch`, `md5crypt` and `Md5Sums`
+//! - `simd`: `Md5::calculate_x4` hashes its 4 inputs in parallel with sse2 on x86_64
+//! - `rayon`: `Md5::calculate_batch_parallel`
+//! - `zeroize`: wipes the state of a `Md5` from memory when it is dropped
+//! - `serde`: serialization of the running state of a `Md5`
+//! - `digest-traits`: the RustCrypto `digest` traits
+//! - `async`: the tokio `AsyncWrite` implementation and `Md5::from_async_reader`
+//! - `bytes`: hashing of `bytes::Bytes` buffers
+//! - `mmap`: `Md5::hash_file_mmap`, hashing a file through a memory map
+//! - `wasm`: javascript bindings, in the `wasm` module
+//! - `cli`: the `md5sum` binary
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod md5_core {
-    use std::num::Wrapping;
+    #[cfg(feature = "alloc")]
+    use alloc::format;
+    #[cfg(feature = "alloc")]
+    use alloc::string::String;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    use core::fmt;
+    use core::hash::Hasher;
+    use core::str::FromStr;
+    #[cfg(feature = "std")]
+    use std::fs::File;
+    #[cfg(feature = "std")]
+    use std::io;
+    #[cfg(feature = "std")]
+    use std::path::{Path, PathBuf};
 
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(into = "SerializedMd5", try_from = "SerializedMd5")
+    )]
     pub struct Md5 {
-        buffer: Vec<u8>,
+        // bytes of the last incomplete block, waiting for more data to be processed
+        buffer: [u8; 64],
+        buffer_len: usize,
         length: u64,
         a0: u32,
         b0: u32,
@@ -10,8 +63,24 @@ pub mod md5_core {
         d0: u32,
     }
 
+    // guards the computed table against mistakes in sine_table, and the literal table against
+    // transcription typos
+    const _: () = {
+        let mut i = 0;
+        while i < 64 {
+            assert!(Md5::PRECOMPUTED_TABLE[i] == Md5::RFC_1321_TABLE[i]);
+            i += 1;
+        }
+    };
+
     impl Md5 {
-        const PRECOMPUTED_TABLE: [u32; 64] = [
+        /// The 64 additive constants of md5 (the table `T` of rfc 1321), one per step of the
+        /// compression function: `floor(2^32 * abs(sin(i + 1)))` for the step `i`. Starting
+        /// point for the variants of `calculate_with_tables`
+        pub const PRECOMPUTED_TABLE: [u32; 64] = Self::sine_table();
+
+        // the table as printed in rfc 1321, checked against sine_table at compile time
+        pub(crate) const RFC_1321_TABLE: [u32; 64] = [
             0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613,
             0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193,
             0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d,
@@ -24,15 +93,73 @@ pub mod md5_core {
             0xeb86d391,
         ];
 
-        const SHIFT_TABLE: [u32; 64] = [
+        // pi * 2^120, rounded down
+        const PI_FIXED_120: u128 = 0x3243f6a8885a308d313198a2e037073;
+
+        // computes floor(2^32 * abs(sin(i + 1))) for the 64 steps without floats, which aren't
+        // fully usable in a const fn: the sine is evaluated in fixed point with 60 fractional
+        // bits, which is more than enough, since none of the 64 values is closer than 0.01 to an
+        // integer once multiplied by 2^32
+        pub(crate) const fn sine_table() -> [u32; 64] {
+            const FRACTION_BITS: u32 = 60;
+
+            let pi = Self::PI_FIXED_120 >> (120 - FRACTION_BITS);
+            let mut table = [0u32; 64];
+            let mut i = 0;
+            while i < 64 {
+                // abs(sin) repeats every pi and is symmetric around pi / 2, so the angle can be
+                // brought into [0, pi / 2], where the taylor series converges quickly. The
+                // reduction is done with 120 fractional bits to keep the error of pi small
+                let x = ((i as u128 + 1) << 120) % Self::PI_FIXED_120;
+                let mut r = x >> (120 - FRACTION_BITS);
+                if 2 * r > pi {
+                    r = pi - r;
+                }
+
+                // sin(r) = r - r^3/3! + r^5/5! - ..., each term computed from the previous one
+                let r2 = (r * r) >> FRACTION_BITS;
+                let mut sine = r;
+                let mut term = r;
+                let mut n = 1;
+                loop {
+                    term = ((term * r2) >> FRACTION_BITS) / ((2 * n) * (2 * n + 1));
+                    if term == 0 {
+                        break;
+                    }
+                    if n % 2 == 1 {
+                        sine -= term;
+                    } else {
+                        sine += term;
+                    }
+                    n += 1;
+                }
+
+                table[i] = (sine >> (FRACTION_BITS - 32)) as u32;
+                i += 1;
+            }
+
+            table
+        }
+
+        /// The left rotation amounts of md5 (the `s` values of rfc 1321), one per step of the
+        /// compression function. Starting point for the variants of `calculate_with_tables`
+        pub const SHIFT_TABLE: [u32; 64] = [
             7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20,
             5, 9, 14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
             6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
         ];
 
+        /// Size of the chunks in which `from_reader` and `hash_file` read their input
+        pub const DEFAULT_CHUNK_SIZE: usize = 8192;
+
+        const SAVED_STATE_VERSION: u8 = 1;
+        // version, 4 words, length and number of buffered bytes
+        const SAVED_STATE_HEADER_LEN: usize = 1 + 4 * 4 + 8 + 1;
+
         pub fn new() -> Self {
             Self {
-                buffer: Vec::new(),
+                buffer: [0; 64],
+                buffer_len: 0,
                 length: 0,
                 a0: 0x67452301,
                 b0: 0xEFCDAB89,
@@ -41,43 +168,104 @@ pub mod md5_core {
             }
         }
 
-        /// Returns a new Md5 object with the updated state of the md5 calculation
-        /// It means that this function is pure (no mutations)
+        /// Starts a new md5 calculation from a non-standard initial vector, with nothing
+        /// processed yet. `Md5::new()` is the same as starting from the rfc 1321 words
+        /// `0x67452301`, `0xEFCDAB89`, `0x98BADCFE` and `0x10325476`
         ///
         /// # Example
         ///
         /// ```
         /// use md5_core::md5_core::Md5;
         ///
-        /// let mut md5 = Md5::new();
-        /// md5 = md5.consume(b"hello");
+        /// let md5 = Md5::with_iv(0, 0, 0, 0).consume(b"helloworld");
+        /// assert_ne!(md5.digest(), Md5::calculate(b"helloworld"));
         /// ```
-        pub fn consume(&self, data: &[u8]) -> Self {
-            let mut buffer = [&self.buffer, data].concat();
-            let mut a0 = self.a0;
-            let mut b0 = self.b0;
-            let mut c0 = self.c0;
-            let mut d0 = self.d0;
-
-            while buffer.len() >= 64 {
-                let digested = Md5::calculate_chunks(&buffer[..64], a0, b0, c0, d0);
-                a0 = (((digested >> 96) & 0xffffffff) as u32).to_be();
-                b0 = (((digested >> 64) & 0xffffffff) as u32).to_be();
-                c0 = (((digested >> 32) & 0xffffffff) as u32).to_be();
-                d0 = (((digested >> 00) & 0xffffffff) as u32).to_be();
-                buffer = buffer[64..].to_vec();
-            }
+        pub fn with_iv(a: u32, b: u32, c: u32, d: u32) -> Self {
+            Self::from_state(a, b, c, d, 0)
+        }
 
+        /// Resumes a md5 calculation from the chaining words `a`, `b`, `c` and `d` and the total
+        /// number of bytes already processed, as returned by `state`. `total_len_bytes` should
+        /// be a multiple of 64, since nothing can be left buffered
+        ///
+        /// The words of a known digest can be recovered by reading each group of 4 bytes of
+        /// `Md5::digest_bytes` as a little-endian u32. This is what makes md5 subject to
+        /// length extension: the hash can be continued without knowing the original message
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let block = [b'a'; 64];
+        /// let (a, b, c, d, length) = Md5::new().consume(&block).state();
+        /// let resumed = Md5::from_state(a, b, c, d, length).consume(b"hello");
+        /// assert_eq!(resumed.digest(), Md5::calculate(&[&block[..], b"hello"].concat()));
+        /// ```
+        pub fn from_state(a: u32, b: u32, c: u32, d: u32, total_len_bytes: u64) -> Self {
             Self {
-                buffer,
-                length: self.length + (data.len() as u64),
-                a0,
-                b0,
-                c0,
-                d0,
+                buffer: [0; 64],
+                buffer_len: 0,
+                length: total_len_bytes,
+                a0: a,
+                b0: b,
+                c0: c,
+                d0: d,
             }
         }
 
+        /// Performs a length extension: given only the digest and the length in bytes of some
+        /// unknown message, returns the digest of the message followed by the glue padding and
+        /// `append`, together with that glue padding. The glue is the padding md5 itself added
+        /// to the original message: the `0x80` byte, zeros up to 8 bytes before the end of a
+        /// block, and the length of the message in bits as a little-endian u64
+        ///
+        /// This is why `md5(key || msg)` can't authenticate messages, see
+        /// `calculate_authenticated`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let secret_message = b"secret:user=guest";
+        /// let known = Md5::calculate(secret_message);
+        ///
+        /// let (forged, glue) = Md5::length_extend(known, 17, b";admin=true");
+        /// let full_message = [&secret_message[..], &glue, b";admin=true"].concat();
+        /// assert_eq!(forged, Md5::calculate(&full_message));
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn length_extend(
+            original_digest: u128,
+            original_len_bytes: u64,
+            append: &[u8],
+        ) -> (u128, Vec<u8>) {
+            // 1 byte for 0x80 and 8 for the length, padded with zeros to a multiple of 64. The
+            // length wraps like in update, which doesn't change it modulo 64
+            let zeros = (64 - original_len_bytes.wrapping_add(9) % 64) % 64;
+            let mut glue = vec![0u8; 1 + zeros as usize + 8];
+            glue[0] = 0x80;
+            let length_start = glue.len() - 8;
+            glue[length_start..].copy_from_slice(&original_len_bytes.wrapping_mul(8).to_le_bytes());
+
+            let mut md5 = Self::from_state(
+                0,
+                0,
+                0,
+                0,
+                original_len_bytes.wrapping_add(glue.len() as u64),
+            );
+            md5.set_state(original_digest);
+            md5.update(append);
+
+            (md5.digest(), glue)
+        }
+
+        /// Returns a new Md5 object with the updated state of the md5 calculation
+        /// It means that this function is pure (no mutations). The state has a fixed size, so
+        /// nothing is allocated, and consuming empty data returns an identical copy
+        ///
         /// # Example
         ///
         /// ```
@@ -85,213 +273,5540 @@ pub mod md5_core {
         ///
         /// let mut md5 = Md5::new();
         /// md5 = md5.consume(b"hello");
-        /// md5 = md5.consume(b"world");
-        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
         /// ```
-        pub fn digest(&self) -> u128 {
-            let preprocessed = Self::preprocess(&self.buffer, self.length * 8);
+        pub fn consume(&self, data: &[u8]) -> Self {
+            let mut md5 = self.clone();
+            md5.update(data);
 
-            return Md5::calculate_chunks(&preprocessed, self.a0, self.b0, self.c0, self.d0);
+            md5
         }
 
-        /// Returns the md5 hash of the input byte array
+        /// Same as `consume`, for a `bytes::Bytes` buffer. The buffer is hashed in place, no
+        /// bytes are copied
+        ///
+        /// # Example
         ///
-        /// # Limitations
+        /// ```
+        /// # #[cfg(feature = "bytes")]
+        /// # {
+        /// use md5_core::md5_core::Md5;
         ///
-        /// Works only with complete bytes (multiples of 8 bits)
+        /// let md5 = Md5::new().consume_bytes(&bytes::Bytes::from_static(b"helloworld"));
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// # }
+        /// ```
+        #[cfg(feature = "bytes")]
+        pub fn consume_bytes(&self, data: &bytes::Bytes) -> Self {
+            self.consume(data)
+        }
+
+        /// Same as `consume`, but accepts anything that can be viewed as bytes, like `&str`,
+        /// `String`, `Vec<u8>` or `Cow<[u8]>`
         ///
         /// # Example
         ///
         /// ```
-        /// assert_eq!(
-        ///     md5_core::md5_core::Md5::calculate(b"helloworld"),
-        ///     0xfc5e038d38a57032085441e7fe7010b0
-        /// );
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume_from("hello").consume_from(String::from("world"));
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
         /// ```
-        pub fn calculate(input: &[u8]) -> u128 {
-            let preprocessed = Self::preprocess(input, (input.len() * 8).try_into().unwrap());
-
-            return Md5::calculate_chunks(
-                &preprocessed,
-                0x67452301u32,
-                0xEFCDAB89u32,
-                0x98BADCFEu32,
-                0x10325476u32,
-            );
+        pub fn consume_from<T: AsRef<[u8]>>(&self, data: T) -> Self {
+            self.consume(data.as_ref())
         }
 
-        fn calculate_chunks(buffer: &[u8], a0: u32, b0: u32, c0: u32, d0: u32) -> u128 {
-            let mut a0 = Wrapping(a0);
-            let mut b0 = Wrapping(b0);
-            let mut c0 = Wrapping(c0);
-            let mut d0 = Wrapping(d0);
-
-            for n in (0..buffer.len()).step_by(64) {
-                let mut a = a0;
-                let mut b = b0;
-                let mut c = c0;
-                let mut d = d0;
-
-                let chunk = &buffer[n..n + 64];
-                let m = [
-                    Self::as_u32_le(&chunk[..4].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[4..8].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[8..12].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[12..16].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[16..20].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[20..24].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[24..28].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[28..32].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[32..36].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[36..40].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[40..44].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[44..48].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[48..52].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[52..56].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[56..60].try_into().unwrap()),
-                    Self::as_u32_le(&chunk[60..64].try_into().unwrap()),
-                ];
-
-                for i in 0..64 {
-                    let mut f;
-                    let g: u32;
-
-                    if i < 16 {
-                        f = (b & c) | (!b & d);
-                        g = i;
-                    } else if i < 32 {
-                        f = (d & b) | (!d & c);
-                        g = (5 * i + 1) % 16;
-                    } else if i < 48 {
-                        f = b ^ c ^ d;
-                        g = (3 * i + 5) % 16;
-                    } else {
-                        f = c ^ (b | !d);
-                        g = (7 * i) % 16;
-                    }
+        /// Updates the state of the md5 calculation in place. This is the mutable
+        /// counterpart of `consume`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut md5 = Md5::new();
+        /// md5.update(b"hello");
+        /// md5.update(b"world");
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn update(&mut self, data: &[u8]) {
+            // nothing to buffer or process, the state stays exactly the same
+            if data.is_empty() {
+                return;
+            }
+            self.length = self.length.wrapping_add(data.len() as u64);
+            let mut data = data;
 
-                    f +=
-                        a + Wrapping(m[g as usize]) + Wrapping(Self::PRECOMPUTED_TABLE[i as usize]);
-                    a = d;
-                    d = c;
-                    c = b;
-                    b += Wrapping(u32::rotate_left(f.0, Self::SHIFT_TABLE[i as usize]));
+            // complete the block left partially filled by the previous calls first
+            if self.buffer_len > 0 {
+                let missing = (64 - self.buffer_len).min(data.len());
+                self.buffer[self.buffer_len..self.buffer_len + missing]
+                    .copy_from_slice(&data[..missing]);
+                self.buffer_len += missing;
+                data = &data[missing..];
+                if self.buffer_len < 64 {
+                    return;
                 }
 
-                a0 += a;
-                b0 += b;
-                c0 += c;
-                d0 += d;
+                let digested =
+                    Md5::calculate_chunks(&self.buffer, self.a0, self.b0, self.c0, self.d0);
+                self.set_state(digested);
+                self.buffer_len = 0;
             }
 
-            return ((a0.0.to_be() as u128) << 96)
-                + ((b0.0.to_be() as u128) << 64)
-                + ((c0.0.to_be() as u128) << 32)
-                + d0.0.to_be() as u128;
+            // the complete blocks are processed straight from the input, only the remaining
+            // bytes are kept in the buffer
+            let complete = data.len() - data.len() % 64;
+            if complete > 0 {
+                let digested =
+                    Md5::calculate_chunks(&data[..complete], self.a0, self.b0, self.c0, self.d0);
+                self.set_state(digested);
+            }
+            self.buffer[..data.len() - complete].copy_from_slice(&data[complete..]);
+            self.buffer_len = data.len() - complete;
         }
 
-        fn preprocess(input: &[u8], original_length_in_bits: u64) -> Vec<u8> {
-            let mut preprocessed = input.to_owned();
-            let original_length = original_length_in_bits;
+        /// Same as `consume`, but feeds every slice of the iterator in order
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let chunks: [&[u8]; 2] = [b"hello", b"world"];
+        /// let md5 = Md5::new().consume_iter(chunks);
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn consume_iter<'a, I: IntoIterator<Item = &'a [u8]>>(&self, chunks: I) -> Self {
+            let mut md5 = self.clone();
+            md5.update_iter(chunks);
+
+            md5
+        }
 
-            let mut n_bytes_to_push = 56 - (preprocessed.len() % 64);
-            if n_bytes_to_push <= 0 {
-                n_bytes_to_push = 64 + n_bytes_to_push;
+        /// Same as `update`, but feeds every slice of the iterator in order
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut md5 = Md5::new();
+        /// md5.update_iter(b"hello world".split(|&c| c == b' '));
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn update_iter<'a, I: IntoIterator<Item = &'a [u8]>>(&mut self, chunks: I) {
+            for chunk in chunks {
+                self.update(chunk);
             }
+        }
 
-            // append bit '1'. The current implementation only works with complete bytes,
-            // so b'10000000 == 0x80
-            preprocessed.push(0x80);
+        /// Returns the current chaining words `[a, b, c, d]`. This is the intermediate state
+        /// after the complete blocks processed so far, not the final digest: the bytes still
+        /// buffered and the padding are not part of it
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(b"less than a block");
+        /// assert_eq!(md5.chaining_words(), [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476]);
+        /// ```
+        pub fn chaining_words(&self) -> [u32; 4] {
+            [self.a0, self.b0, self.c0, self.d0]
+        }
 
-            // push enough zeros to have 448 (mod 512) bits
-            // n_bytes_to_push - 1 because already pushed 0x80 above
-            let mut bytes_to_push = vec![0 as u8; n_bytes_to_push - 1];
-            preprocessed.append(&mut bytes_to_push);
+        /// Returns the chaining words `a`, `b`, `c` and `d` and the total number of bytes
+        /// consumed so far. The words only include the complete blocks processed, not the bytes
+        /// still buffered. See `from_state` for their relation with the digest bytes
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert_eq!(
+        ///     Md5::new().state(),
+        ///     (0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0)
+        /// );
+        /// ```
+        pub fn state(&self) -> (u32, u32, u32, u32, u64) {
+            (self.a0, self.b0, self.c0, self.d0, self.length)
+        }
 
-            preprocessed.append(&mut Self::u64_to_vector_u8_be(original_length as u64));
+        /// Saves the running state of the md5 calculation in a compact byte blob, so it can be
+        /// resumed later with `load_state`
+        ///
+        /// The layout is a version byte (currently 1), the four chaining words as little-endian
+        /// u32, the total length as a little-endian u64, the number of buffered bytes as a
+        /// single byte, and then the buffered bytes themselves
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let saved = Md5::new().consume(b"hello").save_state();
+        /// let md5 = Md5::load_state(&saved).unwrap().consume(b"world");
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn save_state(&self) -> Vec<u8> {
+            let mut saved = Vec::with_capacity(Self::SAVED_STATE_HEADER_LEN + self.buffer_len);
+            saved.push(Self::SAVED_STATE_VERSION);
+            for word in [self.a0, self.b0, self.c0, self.d0] {
+                saved.extend_from_slice(&word.to_le_bytes());
+            }
+            saved.extend_from_slice(&self.length.to_le_bytes());
+            saved.push(self.buffer_len as u8);
+            saved.extend_from_slice(&self.buffer[..self.buffer_len]);
 
-            return preprocessed;
+            saved
         }
 
-        fn u64_to_vector_u8_be(value: u64) -> Vec<u8> {
-            let array: [u8; 8] = [
-                ((value >> 00) & 0xff) as u8,
-                ((value >> 08) & 0xff) as u8,
-                ((value >> 16) & 0xff) as u8,
-                ((value >> 24) & 0xff) as u8,
-                ((value >> 32) & 0xff) as u8,
-                ((value >> 40) & 0xff) as u8,
-                ((value >> 48) & 0xff) as u8,
-                ((value >> 56) & 0xff) as u8,
-            ];
+        /// Resumes a md5 calculation saved with `save_state`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::{Md5, Md5Error, StateError};
+        ///
+        /// assert!(matches!(
+        ///     Md5::load_state(&[2]),
+        ///     Err(Md5Error::State(StateError::BadVersion))
+        /// ));
+        /// ```
+        pub fn load_state(bytes: &[u8]) -> Result<Md5, Md5Error> {
+            match bytes.first() {
+                Some(&Self::SAVED_STATE_VERSION) => {}
+                Some(_) => return Err(Md5Error::State(StateError::BadVersion)),
+                None => return Err(Md5Error::State(StateError::BadLength)),
+            }
+            if bytes.len() < Self::SAVED_STATE_HEADER_LEN {
+                return Err(Md5Error::State(StateError::BadLength));
+            }
+
+            let word =
+                |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+            let mut length = [0u8; 8];
+            length.copy_from_slice(&bytes[17..25]);
+            let buffer_len = bytes[25] as usize;
+            let buffer = &bytes[Self::SAVED_STATE_HEADER_LEN..];
+            if buffer_len >= 64 {
+                return Err(Md5Error::State(StateError::BadBuffer));
+            }
+            if buffer.len() != buffer_len {
+                return Err(Md5Error::State(StateError::BadLength));
+            }
+
+            let mut md5 = Self::from_state(
+                word(1),
+                word(5),
+                word(9),
+                word(13),
+                u64::from_le_bytes(length),
+            );
+            md5.buffer[..buffer_len].copy_from_slice(buffer);
+            md5.buffer_len = buffer_len;
 
-            return array.to_vec();
+            Ok(md5)
         }
 
-        fn as_u32_le(array: &[u8; 4]) -> u32 {
-            ((array[0] as u32) << 0)
-                + ((array[1] as u32) << 8)
-                + ((array[2] as u32) << 16)
-                + ((array[3] as u32) << 24)
+        /// Returns the total number of bytes consumed so far, including the ones still buffered
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(b"hello");
+        /// assert_eq!(md5.bytes_processed(), 5);
+        /// ```
+        pub fn bytes_processed(&self) -> u64 {
+            self.length
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::md5_core;
+        /// Returns the number of bytes buffered while waiting for a complete 64-byte block,
+        /// always between 0 and 63. The next block is compressed once `64 - pending_bytes()`
+        /// more bytes are consumed
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(&[0; 100]);
+        /// assert_eq!(md5.pending_bytes(), 36);
+        /// ```
+        pub fn pending_bytes(&self) -> usize {
+            self.buffer_len
+        }
 
-    use md5_core::Md5;
+        /// Restores the initial state, so the same object can be reused for another
+        /// calculation
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut md5 = Md5::new();
+        /// md5.update(b"hello");
+        /// md5.reset();
+        /// md5.update(b"helloworld");
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn reset(&mut self) {
+            self.buffer_len = 0;
+            self.length = 0;
+            self.a0 = 0x67452301;
+            self.b0 = 0xEFCDAB89;
+            self.c0 = 0x98BADCFE;
+            self.d0 = 0x10325476;
+        }
 
-    #[test]
-    fn calculate_from_empty_returns_0xd41d8cd98f00b204e9800998ecf8427e() {
-        assert_eq!(Md5::calculate(b""), 0xd41d8cd98f00b204e9800998ecf8427e);
-    }
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut md5 = Md5::new();
+        /// md5 = md5.consume(b"hello");
+        /// md5 = md5.consume(b"world");
+        /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn digest(&self) -> u128 {
+            // rfc 1321 only keeps the low 64 bits of the length in bits, so it wraps on purpose
+            let (preprocessed, preprocessed_len) =
+                Self::preprocess(&self.buffer[..self.buffer_len], self.length.wrapping_mul(8));
 
-    #[test]
-    fn calculate_from_helloworld_returns_0xfc5e038d38a57032085441e7fe7010b0() {
-        assert_eq!(
-            Md5::calculate(b"helloworld"),
-            0xfc5e038d38a57032085441e7fe7010b0
-        );
-    }
+            Md5::calculate_chunks(
+                &preprocessed[..preprocessed_len],
+                self.a0,
+                self.b0,
+                self.c0,
+                self.d0,
+            )
+        }
 
-    #[test]
-    fn calculate_from_448_bits_() {
-        assert_eq!(
-            Md5::calculate(b"Lorem ipsum dolor sit amet, consectetur adipiscing odio."),
-            0x2251013dde7bffaa1780cf66fbbaf4bb
-        );
-    }
+        /// Same as `digest`, but returns the 16 bytes of the hash in the standard md5 order
+        /// (the same order `md5sum` prints them)
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(b"helloworld");
+        /// assert_eq!(md5.digest_bytes()[..4], [0xfc, 0x5e, 0x03, 0x8d]);
+        /// ```
+        pub fn digest_bytes(&self) -> [u8; 16] {
+            self.digest().to_be_bytes()
+        }
 
-    #[test]
-    fn calculate_from_two_chunks() {
-        assert_eq!(
-            Md5::calculate(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis. Nullam consectetur nisi non nibh posuere suscipit. Nam velit est, fringilla tincidunt eleifend nec, cursus sit amet metus. Suspendisse id lacus at risus sollicitudin volutpat id in urna. Pellentesque commodo iaculis lectus vitae pulvinar. Morbi ullamcorper ex nisl. Vivamus vel fringilla metus, sit amet malesuada justo. Fusce in lobortis velit. Mauris sed purus mauris. Aenean lobortis bibendum ex quis congue. Etiam sapien nulla, viverra ut lorem blandit."),
-            0xba5e84b5ac5785cca9f18469cc8e0193
-        );
-    }
+        /// Same as `digest`, but returns the hash as a 32 characters lowercase hex string
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(b"helloworld");
+        /// assert_eq!(md5.digest_hex(), "fc5e038d38a57032085441e7fe7010b0");
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn digest_hex(&self) -> String {
+            Self::to_hex(self.digest(), HexCase::Lower)
+        }
 
-    #[test]
-    fn consume_empty_and_digest() {
-        let mut md5 = Md5::new();
-        md5 = md5.consume(b"");
-        assert_eq!(md5.digest(), 0xd41d8cd98f00b204e9800998ecf8427e);
-    }
+        /// Same as `digest_hex`, but with uppercase hex characters
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(b"helloworld");
+        /// assert_eq!(md5.digest_hex_upper(), "FC5E038D38A57032085441E7FE7010B0");
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn digest_hex_upper(&self) -> String {
+            Self::to_hex(self.digest(), HexCase::Upper)
+        }
+
+        /// Same as `digest`, but returns the hash wrapped in a `Md5Digest`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(b"helloworld");
+        /// assert_eq!(md5.to_digest().to_u128(), md5.digest());
+        /// ```
+        pub fn to_digest(&self) -> Md5Digest {
+            Md5Digest::from(self.digest_bytes())
+        }
+
+        /// Returns the hash of everything consumed so far, without changing the running state,
+        /// so more data can still be added afterwards. It's always the same as calling `finalize`
+        /// on a clone
+        ///
+        /// Each call only pads and compresses the buffered bytes, which is at most two blocks, so
+        /// it's cheap enough for progress displays and the result isn't cached (caching it would
+        /// need interior mutability, and `Md5` would stop being `Sync`)
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut md5 = Md5::new();
+        /// md5.update(b"hello");
+        /// assert_eq!(md5.peek_digest(), Md5::calculate_digest(b"hello"));
+        /// md5.update(b"world");
+        /// assert_eq!(md5.peek_digest(), Md5::calculate_digest(b"helloworld"));
+        /// ```
+        pub fn peek_digest(&self) -> Md5Digest {
+            self.to_digest()
+        }
+
+        /// Same as `to_digest`, but takes ownership of the object, so it can't be used anymore
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut md5 = Md5::new();
+        /// md5.update(b"helloworld");
+        /// assert_eq!(md5.finalize().to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn finalize(self) -> Md5Digest {
+            self.to_digest()
+        }
+
+        /// Same as `finalize`, but writes the 16 bytes of the hash, in the standard md5 order,
+        /// into `out` instead of returning them
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut out = [0u8; 16];
+        /// Md5::new().consume(b"helloworld").finalize_into(&mut out);
+        /// assert_eq!(out, Md5::calculate_bytes(b"helloworld"));
+        /// ```
+        pub fn finalize_into(self, out: &mut [u8; 16]) {
+            *out = self.finalize().to_be_bytes();
+        }
+
+        /// Same as `finalize`, but also returns the total number of bytes that were hashed
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let (digest, length) = Md5::new().consume(b"helloworld").finalize_with_length();
+        /// assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// assert_eq!(length, 10);
+        /// ```
+        pub fn finalize_with_length(self) -> (Md5Digest, u64) {
+            let length = self.length;
+
+            (self.finalize(), length)
+        }
+
+        /// Returns whether the hash of the data consumed so far matches the expected hex string
+        /// (either lowercase or uppercase). A malformed expected string never matches
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let md5 = Md5::new().consume(b"helloworld");
+        /// assert!(md5.verify_against("fc5e038d38a57032085441e7fe7010b0"));
+        /// ```
+        pub fn verify_against(&self, expected_hex: &str) -> bool {
+            expected_hex.parse::<Md5Digest>() == Ok(self.to_digest())
+        }
+
+        /// Returns the md5 hash of the input byte array
+        ///
+        /// Use `calculate_bits` for messages that are not a whole number of bytes
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// assert_eq!(
+        ///     md5_core::md5_core::Md5::calculate(b"helloworld"),
+        ///     0xfc5e038d38a57032085441e7fe7010b0
+        /// );
+        /// ```
+        pub fn calculate(input: &[u8]) -> u128 {
+            // update processes the complete blocks straight from the input, so only the last
+            // partial block is copied to be padded
+            let mut md5 = Self::new();
+            md5.update(input);
+
+            md5.digest()
+        }
+
+        /// Same as `calculate`, but accepts anything that can be viewed as bytes, like `&str`,
+        /// `String`, `Vec<u8>` or `Cow<[u8]>`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert_eq!(Md5::calculate_from("helloworld"), Md5::calculate(b"helloworld"));
+        /// ```
+        pub fn calculate_from<T: AsRef<[u8]>>(input: T) -> u128 {
+            Self::calculate(input.as_ref())
+        }
+
+        /// Same as `calculate`, for a `bytes::Bytes` buffer. The buffer is hashed in place, no
+        /// bytes are copied
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// # #[cfg(feature = "bytes")]
+        /// # {
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let buffer = bytes::Bytes::from_static(b"helloworld");
+        /// assert_eq!(Md5::calculate_bytes_crate(&buffer), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// # }
+        /// ```
+        #[cfg(feature = "bytes")]
+        pub fn calculate_bytes_crate(b: &bytes::Bytes) -> u128 {
+            Self::calculate(b)
+        }
+
+        /// Returns the md5 hash of the UTF-8 bytes of the string
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert_eq!(Md5::calculate_str("helloworld"), "fc5e038d38a57032085441e7fe7010b0");
+        /// ```
+        pub fn calculate_str(s: &str) -> Md5Digest {
+            Self::calculate_digest(s.as_bytes())
+        }
+
+        /// Returns the md5 hash of the first `bit_len` bits of `input`
+        ///
+        /// Bits are taken most significant first, so when `bit_len` is not a multiple of 8 the
+        /// message ends in the high bits of the last byte used and its low bits are ignored.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `bit_len` is greater than the number of bits in `input`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// // the single bit '1'
+        /// assert_eq!(Md5::calculate_bits(&[0x80], 1), 0x7e663710ae2348bf0deaca2c79311eae);
+        /// assert_eq!(Md5::calculate_bits(b"helloworld", 80), Md5::calculate(b"helloworld"));
+        /// ```
+        pub fn calculate_bits(input: &[u8], bit_len: u64) -> u128 {
+            match Self::try_calculate_bits(input, bit_len) {
+                Ok(digest) => digest,
+                Err(_) => panic!("bit_len is greater than the length of the input in bits"),
+            }
+        }
+
+        /// Same as `calculate_bits`, but returns `Md5Error::BitLength` instead of panicking when
+        /// `bit_len` is greater than the number of bits in `input`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::{Md5, Md5Error};
+        ///
+        /// assert!(Md5::try_calculate_bits(&[0x80], 1).is_ok());
+        /// assert!(matches!(Md5::try_calculate_bits(&[0x80], 9), Err(Md5Error::BitLength)));
+        /// ```
+        pub fn try_calculate_bits(input: &[u8], bit_len: u64) -> Result<u128, Md5Error> {
+            if bit_len.div_ceil(8) > input.len() as u64 {
+                return Err(Md5Error::BitLength);
+            }
+            let used_bytes = bit_len.div_ceil(8) as usize;
+            let whole_bytes = (bit_len / 8) as usize;
+            let complete_blocks = whole_bytes - whole_bytes % 64;
+
+            let mut md5 = Self::new();
+            md5.update(&input[..complete_blocks]);
+
+            let (preprocessed, len) =
+                Self::preprocess(&input[complete_blocks..used_bytes], bit_len);
+            Ok(Self::calculate_chunks(
+                &preprocessed[..len],
+                md5.a0,
+                md5.b0,
+                md5.c0,
+                md5.d0,
+            ))
+        }
+
+        /// Same as `calculate`, but returns the 16 bytes of the hash in the standard md5 order
+        /// (the same order `md5sum` prints them)
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// assert_eq!(
+        ///     md5_core::md5_core::Md5::calculate_bytes(b"helloworld")[..4],
+        ///     [0xfc, 0x5e, 0x03, 0x8d]
+        /// );
+        /// ```
+        pub fn calculate_bytes(input: &[u8]) -> [u8; 16] {
+            Self::calculate(input).to_be_bytes()
+        }
+
+        /// Same as `calculate`, but returns the hash as a 32 characters lowercase hex string
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// assert_eq!(
+        ///     md5_core::md5_core::Md5::calculate_hex(b""),
+        ///     "d41d8cd98f00b204e9800998ecf8427e"
+        /// );
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn calculate_hex(input: &[u8]) -> String {
+            Self::calculate_hex_cased(input, HexCase::Lower)
+        }
+
+        /// Same as `calculate_hex`, but with uppercase hex characters
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// assert_eq!(
+        ///     md5_core::md5_core::Md5::calculate_hex_upper(b""),
+        ///     "D41D8CD98F00B204E9800998ECF8427E"
+        /// );
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn calculate_hex_upper(input: &[u8]) -> String {
+            Self::calculate_hex_cased(input, HexCase::Upper)
+        }
+
+        /// Same as `calculate_hex`, but the letter case is chosen at runtime
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::{HexCase, Md5};
+        ///
+        /// assert_eq!(
+        ///     Md5::calculate_hex_cased(b"", HexCase::Upper),
+        ///     "D41D8CD98F00B204E9800998ECF8427E"
+        /// );
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn calculate_hex_cased(input: &[u8], case: HexCase) -> String {
+            Self::to_hex(Self::calculate(input), case)
+        }
+
+        /// Same as `calculate`, but returns the hash wrapped in a `Md5Digest`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert_eq!(
+        ///     Md5::calculate_digest(b"helloworld").to_u128(),
+        ///     0xfc5e038d38a57032085441e7fe7010b0
+        /// );
+        /// ```
+        pub fn calculate_digest(input: &[u8]) -> Md5Digest {
+            Md5Digest::from(Self::calculate_bytes(input))
+        }
+
+        /// Returns the md5 hash of the bytes of an iterator, without collecting them first. The
+        /// bytes are gathered into 64-byte blocks that are compressed as soon as they are full
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_iter(b"helloworld".iter().copied());
+        /// assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn calculate_iter<I: IntoIterator<Item = u8>>(iter: I) -> Md5Digest {
+            let mut md5 = Self::new();
+            md5.extend(iter);
+
+            md5.finalize()
+        }
+
+        /// Same as `calculate_digest`, but returns `Md5Error::Length` without hashing anything
+        /// when the input is longer than `max_len` bytes. Hashing never copies the input, so
+        /// this is a bound on the time spent on untrusted input, the one-shot counterpart of
+        /// `from_reader_limited`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::{Md5, Md5Error};
+        ///
+        /// assert_eq!(
+        ///     Md5::try_calculate(b"helloworld", 10).unwrap(),
+        ///     Md5::calculate_digest(b"helloworld")
+        /// );
+        /// assert!(matches!(Md5::try_calculate(b"helloworld", 9), Err(Md5Error::Length)));
+        /// ```
+        pub fn try_calculate(input: &[u8], max_len: usize) -> Result<Md5Digest, Md5Error> {
+            if input.len() > max_len {
+                return Err(Md5Error::Length);
+            }
+
+            Ok(Self::calculate_digest(input))
+        }
+
+        /// Returns a code authenticating `msg` with the secret `key`, computed as HMAC-MD5
+        /// (rfc 2104), the safe way of authenticating a message with md5
+        ///
+        /// The naive `md5(key || msg)` must not be used for this: since a md5 digest is the whole
+        /// internal state after the padded message, anyone who knows `md5(key || msg)` and the
+        /// length of `key || msg` can keep hashing from it, and produce a valid code for `msg`
+        /// followed by the padding and any data of their choice without knowing the key (a
+        /// length extension attack, see `Md5::length_extend`). HMAC hashes the result of the
+        /// inner hash again with the key, so the exposed digest can't be continued
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let code = Md5::calculate_authenticated(b"Jefe", b"what do ya want for nothing?");
+        /// assert_eq!(code.to_u128(), 0x750c783e6ab0b503eaa86e310a5db738);
+        /// ```
+        pub fn calculate_authenticated(key: &[u8], msg: &[u8]) -> Md5Digest {
+            let mut hmac = HmacMd5::new(key);
+            hmac.update(msg);
+
+            hmac.finalize()
+        }
+
+        /// Returns the md5 hash of the salt and the input joined together, the salt going
+        /// first when `salt_first` is true. This is what legacy `md5(salt || password)` and
+        /// `md5(password || salt)` schemes store. Nothing is allocated, both slices are fed to
+        /// the calculation one after the other. It must not be used to authenticate messages,
+        /// use `calculate_authenticated` for that
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_salted(b"hello", b"world", true);
+        /// assert_eq!(digest, Md5::calculate_digest(b"helloworld"));
+        /// ```
+        pub fn calculate_salted(salt: &[u8], input: &[u8], salt_first: bool) -> Md5Digest {
+            let (first, second) = if salt_first {
+                (salt, input)
+            } else {
+                (input, salt)
+            };
+
+            let mut md5 = Self::new();
+            md5.update(first);
+            md5.update(second);
+
+            md5.finalize()
+        }
+
+        /// Returns the md5 hash of the input re-hashed `rounds - 1` more times, that is
+        /// `md5(md5(...md5(input)))` with `rounds` calls, as used by old key derivation schemes.
+        /// There is no digest to return without hashing the input at least once, so `rounds == 0`
+        /// is the same as `rounds == 1`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let twice = Md5::calculate_iterated(b"helloworld", 2);
+        /// assert_eq!(twice, Md5::calculate_digest(&Md5::calculate_bytes(b"helloworld")));
+        /// ```
+        pub fn calculate_iterated(input: &[u8], rounds: u32) -> Md5Digest {
+            let mut digest = Self::calculate_digest(input);
+            for _ in 1..rounds {
+                digest = Self::calculate_digest(digest.as_bytes());
+            }
+
+            digest
+        }
+
+        /// Returns whether the md5 hash of the input byte array matches the expected hex string
+        /// (either lowercase or uppercase). A malformed expected string never matches
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert!(Md5::verify(b"helloworld", "fc5e038d38a57032085441e7fe7010b0"));
+        /// assert!(!Md5::verify(b"helloworld", "not a md5"));
+        /// ```
+        pub fn verify(input: &[u8], expected_hex: &str) -> bool {
+            Self::try_verify(input, expected_hex).unwrap_or(false)
+        }
+
+        /// Same as `verify`, but returns an error if the expected string is malformed
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::{Md5, Md5Error, Md5ParseError};
+        ///
+        /// assert!(Md5::try_verify(b"helloworld", "fc5e038d38a57032085441e7fe7010b0").unwrap());
+        /// assert!(matches!(
+        ///     Md5::try_verify(b"helloworld", "fc5e"),
+        ///     Err(Md5Error::Parse(Md5ParseError::BadLength))
+        /// ));
+        /// ```
+        pub fn try_verify(input: &[u8], expected_hex: &str) -> Result<bool, Md5Error> {
+            let expected = expected_hex.parse::<Md5Digest>()?;
+
+            Ok(expected == Self::calculate_digest(input))
+        }
+
+        /// Returns the md5 hash of everything read from the reader until its end. The data is
+        /// read in chunks of `DEFAULT_CHUNK_SIZE` (8 KiB), so it is never fully loaded into memory
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        /// use std::io::Cursor;
+        ///
+        /// let digest = Md5::from_reader(Cursor::new(b"helloworld")).unwrap();
+        /// assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn from_reader<R: io::Read>(reader: R) -> io::Result<Md5Digest> {
+            let mut md5 = Self::new();
+            md5.update_from_reader(reader, &mut [0u8; Self::DEFAULT_CHUNK_SIZE], |_| {})?;
+
+            Ok(md5.finalize())
+        }
+
+        /// Same as `from_reader`, for a reader behind a trait object, like a `Box<dyn Read>`.
+        /// Only this single version of the reading code is compiled, whatever the reader is
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        /// use std::io::{Cursor, Read};
+        ///
+        /// let mut reader: Box<dyn Read> = Box::new(Cursor::new(b"helloworld"));
+        /// let digest = Md5::from_dyn_reader(&mut *reader).unwrap();
+        /// assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn from_dyn_reader(reader: &mut dyn io::Read) -> io::Result<Md5Digest> {
+            Self::from_reader(reader)
+        }
+
+        /// Same as `from_reader`, but reads the data in chunks of `chunk_size` bytes.
+        /// Returns an `InvalidInput` error if `chunk_size` is 0
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        /// use std::io::Cursor;
+        ///
+        /// let digest = Md5::from_reader_with_capacity(Cursor::new(b"helloworld"), 1 << 20);
+        /// assert_eq!(digest.unwrap().to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn from_reader_with_capacity<R: io::Read>(
+            reader: R,
+            chunk_size: usize,
+        ) -> io::Result<Md5Digest> {
+            if chunk_size == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "chunk size must be greater than 0",
+                ));
+            }
+
+            let mut md5 = Self::new();
+            md5.update_from_reader(reader, &mut vec![0u8; chunk_size], |_| {})?;
+
+            Ok(md5.finalize())
+        }
+
+        /// Same as `from_reader`, but calls `on_progress` with the total number of bytes
+        /// read so far after each chunk. For an empty input it is called once with 0
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        /// use std::io::Cursor;
+        ///
+        /// let mut read = 0;
+        /// let digest = Md5::from_reader_with_progress(Cursor::new(b"helloworld"), |n| read = n);
+        /// assert_eq!(digest.unwrap().to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// assert_eq!(read, 10);
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn from_reader_with_progress<R: io::Read, F: FnMut(u64)>(
+            reader: R,
+            mut on_progress: F,
+        ) -> io::Result<Md5Digest> {
+            let mut md5 = Self::new();
+            md5.update_from_reader(
+                reader,
+                &mut [0u8; Self::DEFAULT_CHUNK_SIZE],
+                &mut on_progress,
+            )?;
+            if md5.length == 0 {
+                on_progress(0);
+            }
+
+            Ok(md5.finalize())
+        }
+
+        /// Same as `from_reader`, but fails with `LimitError::Exceeded` as soon as the reader
+        /// has more than `max_bytes` bytes. A reader with exactly `max_bytes` bytes is accepted.
+        /// At most one byte past the limit is read, and it is never hashed
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::{LimitError, Md5};
+        /// use std::io::Cursor;
+        ///
+        /// let digest = Md5::from_reader_limited(Cursor::new(b"helloworld"), 10).unwrap();
+        /// assert_eq!(digest, "fc5e038d38a57032085441e7fe7010b0");
+        ///
+        /// let too_long = Md5::from_reader_limited(Cursor::new(b"helloworld"), 9);
+        /// assert!(matches!(too_long, Err(LimitError::Exceeded)));
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn from_reader_limited<R: io::Read>(
+            reader: R,
+            max_bytes: u64,
+        ) -> Result<Md5Digest, LimitError> {
+            Md5Builder::new().limit(max_bytes).hash_reader(reader)
+        }
+
+        /// Returns the md5 hash of the lines of the reader, fed one at a time. With
+        /// `include_newlines` the lines are hashed exactly as read, so the result is the same as
+        /// `from_reader`. Without it every `\n` or `\r\n` line ending is left out, like
+        /// `BufRead::lines` does, and the lines are hashed as if they were concatenated
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::hash_lines(&b"hello\nworld\n"[..], false).unwrap();
+        /// assert_eq!(digest, Md5::calculate_digest(b"helloworld"));
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn hash_lines<R: io::BufRead>(
+            mut reader: R,
+            include_newlines: bool,
+        ) -> io::Result<Md5Digest> {
+            let mut md5 = Self::new();
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line)? > 0 {
+                let mut content = &line[..];
+                if !include_newlines {
+                    // like BufRead::lines, a '\r' is only part of the line ending right before
+                    // a '\n', so a last line ending with a lone '\r' keeps it
+                    if let Some(stripped) = content.strip_suffix(b"\n") {
+                        content = stripped.strip_suffix(b"\r").unwrap_or(stripped);
+                    }
+                }
+                md5.update(content);
+                line.clear();
+            }
+
+            Ok(md5.finalize())
+        }
+
+        /// Same as `from_reader`, but for a tokio `AsyncRead`. An empty reader gives the hash of
+        /// the empty input
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// # #[cfg(feature = "async")]
+        /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::from_async_reader(&b"helloworld"[..]).await.unwrap();
+        /// assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// # });
+        /// ```
+        #[cfg(feature = "async")]
+        pub async fn from_async_reader<R: tokio::io::AsyncRead + Unpin>(
+            mut reader: R,
+        ) -> io::Result<Md5Digest> {
+            use tokio::io::AsyncReadExt;
+
+            let mut md5 = Self::new();
+            let mut chunk = vec![0u8; Self::DEFAULT_CHUNK_SIZE];
+            loop {
+                match reader.read(&mut chunk).await {
+                    Ok(0) => return Ok(md5.finalize()),
+                    Ok(n) => md5.update(&chunk[..n]),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        /// Returns the md5 hash of everything read from the readers, one after the other, as if
+        /// they were a single concatenated stream. Nothing is buffered besides the read chunk
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::from_readers([&b"hello"[..], &b"world"[..]]).unwrap();
+        /// assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn from_readers<R: io::Read>(
+            readers: impl IntoIterator<Item = R>,
+        ) -> io::Result<Md5Digest> {
+            let mut md5 = Self::new();
+            let mut chunk = [0u8; Self::DEFAULT_CHUNK_SIZE];
+            for reader in readers {
+                md5.update_from_reader(reader, &mut chunk, |_| {})?;
+            }
+
+            Ok(md5.finalize())
+        }
+
+        /// Returns whether everything read from the reader hashes to `expected`, comparing the
+        /// digests in constant time. A md5 mismatch can only be known once the last byte is
+        /// hashed, so the whole reader is always read, even when the data is corrupted early
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let expected = Md5::calculate_digest(b"helloworld");
+        /// assert!(Md5::verify_reader(&b"helloworld"[..], &expected).unwrap());
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn verify_reader<R: io::Read>(reader: R, expected: &Md5Digest) -> io::Result<bool> {
+            Ok(Self::from_reader(reader)?.ct_eq(expected))
+        }
+
+        /// Returns the md5 hash of the contents of the file. The file is streamed, so it is never
+        /// fully loaded into memory
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::hash_file("Cargo.toml").unwrap();
+        /// println!("{}", digest);
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn hash_file<P: AsRef<Path>>(path: P) -> io::Result<Md5Digest> {
+            let file = File::open(path)?;
+
+            Self::from_reader(io::BufReader::new(file))
+        }
+
+        /// Same as `hash_file`, but the file is memory mapped and hashed in a single pass, which
+        /// is usually faster for very large files. Empty files are hashed without being mapped,
+        /// since mapping 0 bytes fails on some platforms
+        ///
+        /// The file must not be modified while it is being hashed: the mapped memory would change
+        /// under the hash, or the process could be killed if the file gets truncated
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::hash_file_mmap("Cargo.toml").unwrap();
+        /// println!("{}", digest);
+        /// ```
+        #[cfg(feature = "mmap")]
+        pub fn hash_file_mmap<P: AsRef<Path>>(path: P) -> io::Result<Md5Digest> {
+            let file = File::open(path)?;
+            if file.metadata()?.len() == 0 {
+                return Ok(Self::calculate_digest(b""));
+            }
+
+            // SAFETY: the mapping is only read while it is alive, and the documentation asks
+            // callers not to modify the file in the meantime
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+
+            Ok(Self::calculate_digest(&map))
+        }
+
+        /// Copies everything from the reader to the writer, hashing the data on the way.
+        /// Returns the number of bytes copied and their md5 hash
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        /// use std::io::Cursor;
+        ///
+        /// let mut copy = Vec::new();
+        /// let (copied, digest) = Md5::copy_hashed(Cursor::new(b"helloworld"), &mut copy).unwrap();
+        /// assert_eq!(copy, b"helloworld");
+        /// assert_eq!(copied, 10);
+        /// assert_eq!(digest, "fc5e038d38a57032085441e7fe7010b0");
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn copy_hashed<R: io::Read, W: io::Write>(
+            mut reader: R,
+            mut writer: W,
+        ) -> io::Result<(u64, Md5Digest)> {
+            let mut md5 = Self::new();
+            let mut chunk = [0u8; Self::DEFAULT_CHUNK_SIZE];
+            loop {
+                match reader.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        // write_all keeps writing until the writer took the whole chunk
+                        writer.write_all(&chunk[..n])?;
+                        md5.update(&chunk[..n]);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            Ok((md5.length, md5.finalize()))
+        }
+
+        /// Returns whether the contents of the file hash to `expected_hex`, comparing the
+        /// digests in constant time. The file is streamed like in `hash_file`
+        ///
+        /// The expected hash is parsed before the file is opened, so a malformed hash is
+        /// reported as `VerifyError::Parse` even if the file is missing
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let intact = Md5::verify_file("release.tar.gz", "fc5e038d38a57032085441e7fe7010b0");
+        /// assert!(intact.unwrap());
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn verify_file<P: AsRef<Path>>(
+            path: P,
+            expected_hex: &str,
+        ) -> Result<bool, VerifyError> {
+            let expected = expected_hex.parse::<Md5Digest>()?;
+
+            Ok(Self::hash_file(path)?.ct_eq(&expected))
+        }
+
+        /// Returns the md5 hash of every regular file under the directory, walking all its
+        /// subdirectories. Each file is returned with its path relative to `root`, and the
+        /// files are sorted by path, so the result doesn't depend on the order the file system
+        /// lists them
+        ///
+        /// Symbolic links are not followed, neither to files nor to directories, and they are
+        /// skipped like any other file that is not a regular file (sockets, fifos, devices)
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// for (path, digest) in Md5::hash_dir("release").unwrap() {
+        ///     println!("{}", digest.to_md5sum_line(&path.to_string_lossy(), false));
+        /// }
+        /// ```
+        #[cfg(feature = "std")]
+        pub fn hash_dir<P: AsRef<Path>>(root: P) -> io::Result<Vec<(PathBuf, Md5Digest)>> {
+            let root = root.as_ref();
+
+            let mut files = Vec::new();
+            let mut dirs = vec![PathBuf::new()];
+            while let Some(dir) = dirs.pop() {
+                for entry in std::fs::read_dir(root.join(&dir))? {
+                    let entry = entry?;
+                    // the file type of an entry is the one of the link itself, not its target
+                    let file_type = entry.file_type()?;
+                    let path = dir.join(entry.file_name());
+                    if file_type.is_dir() {
+                        dirs.push(path);
+                    } else if file_type.is_file() {
+                        files.push(path);
+                    }
+                }
+            }
+            files.sort();
+
+            files
+                .into_iter()
+                .map(|path| {
+                    let digest = Self::hash_file(root.join(&path))?;
+                    Ok((path, digest))
+                })
+                .collect()
+        }
+
+        // overwrites the whole state with zeros. Volatile writes are used so the compiler can't
+        // remove them, even when the object is never read again
+        #[cfg(feature = "zeroize")]
+        pub(crate) fn wipe(&mut self) {
+            // SAFETY: all the pointers come from references to fields of self
+            unsafe {
+                for byte in self.buffer.iter_mut() {
+                    core::ptr::write_volatile(byte, 0);
+                }
+                core::ptr::write_volatile(&mut self.buffer_len, 0);
+                core::ptr::write_volatile(&mut self.length, 0);
+                core::ptr::write_volatile(&mut self.a0, 0);
+                core::ptr::write_volatile(&mut self.b0, 0);
+                core::ptr::write_volatile(&mut self.c0, 0);
+                core::ptr::write_volatile(&mut self.d0, 0);
+            }
+            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        }
+
+        fn set_state(&mut self, digested: u128) {
+            // the digest bytes are the chaining words in little-endian order, so this does not
+            // depend on the endianness of the platform
+            let [a0, a1, a2, a3, b0, b1, b2, b3, c0, c1, c2, c3, d0, d1, d2, d3] =
+                digested.to_be_bytes();
+            self.a0 = u32::from_le_bytes([a0, a1, a2, a3]);
+            self.b0 = u32::from_le_bytes([b0, b1, b2, b3]);
+            self.c0 = u32::from_le_bytes([c0, c1, c2, c3]);
+            self.d0 = u32::from_le_bytes([d0, d1, d2, d3]);
+        }
+
+        /// Returns the ETag S3 gives to an object uploaded in parts of `part_size` bytes: the hex
+        /// md5 hash of the concatenated raw md5 hashes of the parts, followed by `-` and the
+        /// number of parts. Data that fits in a single part gets its plain hex md5 hash, with no
+        /// suffix. The parts are hashed with `calculate_batch`, so up to 4 at a time
+        ///
+        /// # Panics
+        ///
+        /// Panics if `part_size` is 0
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert_eq!(Md5::etag(b"helloworld", 5), "065947336a2f2a95ba8899f3675c3be6-2");
+        /// assert_eq!(Md5::etag(b"helloworld", 10), "fc5e038d38a57032085441e7fe7010b0");
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn etag(data: &[u8], part_size: usize) -> String {
+            assert!(part_size > 0, "part_size must be greater than 0");
+            if data.len() <= part_size {
+                return Self::calculate_hex(data);
+            }
+
+            let parts: Vec<&[u8]> = data.chunks(part_size).collect();
+            let digests: Vec<u8> = Self::calculate_batch(&parts)
+                .iter()
+                .flat_map(|digest| digest.to_be_bytes())
+                .collect();
+
+            format!("{}-{}", Self::calculate_hex(&digests), parts.len())
+        }
+
+        /// Returns the root of a md5 Merkle tree over the data split in leaves of `leaf_size`
+        /// bytes
+        ///
+        /// The scheme is:
+        /// - each leaf is the md5 hash of `leaf_size` bytes of the data, in order. The last leaf
+        ///   may be shorter, and empty data has a single leaf, the hash of the empty input
+        /// - each parent is the md5 hash of the 16 bytes of its left child followed by the 16
+        ///   bytes of its right child. On a level with an odd number of nodes the last node is
+        ///   paired with itself
+        /// - the root is the single node of the last level, so data of at most `leaf_size` bytes
+        ///   has the same root as its plain md5 hash
+        ///
+        /// Leaves and parents are hashed the same way, so the root of a tree is also the root of
+        /// the data made of the two digests under it. This is meant for content addressing, not
+        /// for detecting forged trees
+        ///
+        /// # Panics
+        ///
+        /// Panics if `leaf_size` is 0
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let hello = Md5::calculate_bytes(b"hello");
+        /// let world = Md5::calculate_bytes(b"world");
+        /// assert_eq!(
+        ///     Md5::merkle_root(b"helloworld", 5),
+        ///     Md5::calculate_digest(&[hello, world].concat())
+        /// );
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn merkle_root(data: &[u8], leaf_size: usize) -> Md5Digest {
+            assert!(leaf_size > 0, "leaf_size must be greater than 0");
+
+            let mut level: Vec<Md5Digest> =
+                data.chunks(leaf_size).map(Self::calculate_digest).collect();
+            if level.is_empty() {
+                level.push(Self::calculate_digest(b""));
+            }
+
+            while level.len() > 1 {
+                level = level
+                    .chunks(2)
+                    .map(|pair| {
+                        let right = pair.get(1).unwrap_or(&pair[0]);
+                        let mut md5 = Self::new();
+                        md5.update(pair[0].as_bytes());
+                        md5.update(right.as_bytes());
+                        md5.finalize()
+                    })
+                    .collect();
+            }
+
+            level[0]
+        }
+
+        /// Returns the md5 hashes of 4 inputs at once. With the `simd` feature on x86_64 the
+        /// inputs are processed in parallel, otherwise this is the same as calling
+        /// `calculate_digest` on each of them
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digests = Md5::calculate_x4([b"hello", b"world", b"helloworld", b""]);
+        /// assert_eq!(digests[2].to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn calculate_x4(inputs: [&[u8]; 4]) -> [Md5Digest; 4] {
+            #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+            // SAFETY: sse2 is part of the x86_64 baseline, so every x86_64 cpu supports it
+            return unsafe { Self::calculate_x4_sse2(inputs) };
+
+            #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+            inputs.map(Self::calculate_digest)
+        }
+
+        /// Returns the md5 hashes of all the inputs, in the same order. The inputs are
+        /// processed 4 at a time with `calculate_x4`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digests = Md5::calculate_batch(&[b"hello", b"helloworld"]);
+        /// assert_eq!(digests[1].to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn calculate_batch(inputs: &[&[u8]]) -> Vec<Md5Digest> {
+            let mut digests = Vec::with_capacity(inputs.len());
+
+            let mut groups = inputs.chunks_exact(4);
+            for group in &mut groups {
+                digests.extend(Self::calculate_x4([group[0], group[1], group[2], group[3]]));
+            }
+            digests.extend(
+                groups
+                    .remainder()
+                    .iter()
+                    .map(|input| Self::calculate_digest(input)),
+            );
+
+            digests
+        }
+
+        /// Same as `calculate_batch`, but the inputs are hashed in parallel with rayon
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digests = Md5::calculate_batch_parallel(&[b"hello", b"helloworld"]);
+        /// assert_eq!(digests[1].to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        #[cfg(feature = "rayon")]
+        pub fn calculate_batch_parallel(inputs: &[&[u8]]) -> Vec<Md5Digest> {
+            use rayon::prelude::*;
+
+            inputs
+                .par_iter()
+                .map(|input| Self::calculate_digest(input))
+                .collect()
+        }
+
+        /// Runs md5 with only the first `rounds` of the 64 steps of the compression function
+        /// applied to each block, for studying how the weaknesses of md5 build up step by step
+        ///
+        /// **This is NOT md5** for any `rounds` other than 64: the result is only meaningful for
+        /// cryptanalysis experiments, and must never be used as, or compared to, a real md5 hash
+        ///
+        /// # Panics
+        ///
+        /// Panics if `rounds` is greater than 64
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert_eq!(Md5::calculate_rounds(b"abc", 64), Md5::calculate(b"abc"));
+        /// assert_ne!(Md5::calculate_rounds(b"abc", 16), Md5::calculate(b"abc"));
+        /// ```
+        pub fn calculate_rounds(input: &[u8], rounds: usize) -> u128 {
+            assert!(
+                rounds <= 64,
+                "md5 has 64 steps, but {} were requested",
+                rounds
+            );
+
+            Self::calculate_with_compress(input, |state, block| {
+                Self::compress_steps(
+                    state,
+                    block,
+                    &Self::PRECOMPUTED_TABLE,
+                    &Self::SHIFT_TABLE,
+                    rounds,
+                )
+            })
+        }
+
+        /// Runs md5 with caller supplied additive constants (`t`, the table `T` of rfc 1321)
+        /// and left rotation amounts (`s`), for studying how the design choices of md5 affect
+        /// it. Passing `PRECOMPUTED_TABLE` and `SHIFT_TABLE` gives the real md5 hash
+        ///
+        /// **This is NOT md5** with any other tables, so the result must never be used as, or
+        /// compared to, a real md5 hash
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// assert_eq!(
+        ///     Md5::calculate_with_tables(b"abc", &Md5::PRECOMPUTED_TABLE, &Md5::SHIFT_TABLE),
+        ///     Md5::calculate(b"abc")
+        /// );
+        ///
+        /// // no rotations at all
+        /// let variant = Md5::calculate_with_tables(b"abc", &Md5::PRECOMPUTED_TABLE, &[0; 64]);
+        /// assert_ne!(variant, Md5::calculate(b"abc"));
+        /// ```
+        pub fn calculate_with_tables(input: &[u8], t: &[u32; 64], s: &[u32; 64]) -> u128 {
+            Self::calculate_with_compress(input, |state, block| {
+                Self::compress_steps(state, block, t, s, 64)
+            })
+        }
+
+        /// Same as `calculate`, but it is a `const fn`, so the hash can be calculated at
+        /// compile time
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// const HELLOWORLD: u128 = Md5::calculate_const(b"helloworld");
+        /// assert_eq!(HELLOWORLD, 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub const fn calculate_const(input: &[u8]) -> u128 {
+            let mut state = [0x67452301u32, 0xEFCDAB89u32, 0x98BADCFEu32, 0x10325476u32];
+
+            let complete = input.len() - input.len() % 64;
+            let mut n = 0;
+            while n < complete {
+                let mut block = [0u8; 64];
+                let mut i = 0;
+                while i < 64 {
+                    block[i] = input[n + i];
+                    i += 1;
+                }
+                state = Self::compress(state, &block);
+                n += 64;
+            }
+
+            // the remaining bytes, the '1' bit, the zeros and the length in bits fill one block,
+            // or two if there is no room left for the length after the remaining bytes
+            let remaining = input.len() - complete;
+            let mut tail = [0u8; 128];
+            let mut i = 0;
+            while i < remaining {
+                tail[i] = input[complete + i];
+                i += 1;
+            }
+            tail[remaining] = 0x80;
+
+            let tail_len = if remaining < 56 { 64 } else { 128 };
+            let length = ((input.len() as u64).wrapping_mul(8)).to_le_bytes();
+            let mut i = 0;
+            while i < 8 {
+                tail[tail_len - 8 + i] = length[i];
+                i += 1;
+            }
+
+            let mut n = 0;
+            while n < tail_len {
+                let mut block = [0u8; 64];
+                let mut i = 0;
+                while i < 64 {
+                    block[i] = tail[n + i];
+                    i += 1;
+                }
+                state = Self::compress(state, &block);
+                n += 64;
+            }
+
+            Self::state_to_digest(state[0], state[1], state[2], state[3])
+        }
+
+        /// Returns, for each of the 64 steps of the compression function, the nonlinear function
+        /// it applies and the index of the message word it adds, for visualizing or checking the
+        /// md5 schedule
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::{Md5, RoundFn};
+        ///
+        /// const SCHEDULE: [(RoundFn, usize); 64] = Md5::round_schedule();
+        /// assert_eq!(SCHEDULE[0], (RoundFn::F, 0));
+        /// assert_eq!(SCHEDULE[16], (RoundFn::G, 1));
+        /// assert_eq!(SCHEDULE[32], (RoundFn::H, 5));
+        /// assert_eq!(SCHEDULE[48], (RoundFn::I, 0));
+        /// ```
+        pub const fn round_schedule() -> [(RoundFn, usize); 64] {
+            let mut schedule = [(RoundFn::F, 0); 64];
+            let mut i = 0;
+            while i < 64 {
+                // same formulas as in compress_steps
+                schedule[i] = if i < 16 {
+                    (RoundFn::F, i)
+                } else if i < 32 {
+                    (RoundFn::G, (5 * i + 1) % 16)
+                } else if i < 48 {
+                    (RoundFn::H, (3 * i + 5) % 16)
+                } else {
+                    (RoundFn::I, (7 * i) % 16)
+                };
+                i += 1;
+            }
+
+            schedule
+        }
+
+        /// The md5 compression function: processes exactly one 64-byte block and returns the
+        /// updated chaining words `[a, b, c, d]`. No padding is done, so hashing a message with
+        /// it means padding the message yourself and starting from the standard initial words
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// // "abc" padded to a single block: the 0x80 terminator, then its length in bits
+        /// let mut block = [0u8; 64];
+        /// block[..4].copy_from_slice(b"abc\x80");
+        /// block[56] = 24;
+        ///
+        /// let state = Md5::compress([0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476], &block);
+        /// assert_eq!(state, [0x98500190, 0xb04fd23c, 0x7d3f96d6, 0x727fe128]);
+        /// ```
+        pub const fn compress(state: [u32; 4], block: &[u8; 64]) -> [u32; 4] {
+            Self::compress_steps(
+                state,
+                block,
+                &Self::PRECOMPUTED_TABLE,
+                &Self::SHIFT_TABLE,
+                64,
+            )
+        }
+
+        // the compression function with the given constants and rotations, stopping after the
+        // first `steps` of the 64 steps. Only what is allowed in a const fn is used, so
+        // calculate_const can use it too
+        const fn compress_steps(
+            state: [u32; 4],
+            block: &[u8; 64],
+            t: &[u32; 64],
+            s: &[u32; 64],
+            steps: usize,
+        ) -> [u32; 4] {
+            let mut m = [0u32; 16];
+            let mut i = 0;
+            while i < 16 {
+                m[i] = u32::from_le_bytes([
+                    block[4 * i],
+                    block[4 * i + 1],
+                    block[4 * i + 2],
+                    block[4 * i + 3],
+                ]);
+                i += 1;
+            }
+
+            let [mut a, mut b, mut c, mut d] = state;
+            let mut i = 0;
+            while i < steps {
+                let (f, g) = if i < 16 {
+                    ((b & c) | (!b & d), i)
+                } else if i < 32 {
+                    ((d & b) | (!d & c), (5 * i + 1) % 16)
+                } else if i < 48 {
+                    (b ^ c ^ d, (3 * i + 5) % 16)
+                } else {
+                    (c ^ (b | !d), (7 * i) % 16)
+                };
+
+                let f = f.wrapping_add(a).wrapping_add(m[g]).wrapping_add(t[i]);
+                a = d;
+                d = c;
+                c = b;
+                b = b.wrapping_add(f.rotate_left(s[i]));
+                i += 1;
+            }
+
+            [
+                state[0].wrapping_add(a),
+                state[1].wrapping_add(b),
+                state[2].wrapping_add(c),
+                state[3].wrapping_add(d),
+            ]
+        }
+
+        // hashes the input with a replacement for the compression function, padding it the
+        // same way as md5 does
+        fn calculate_with_compress<C: Fn([u32; 4], &[u8; 64]) -> [u32; 4]>(
+            input: &[u8],
+            compress: C,
+        ) -> u128 {
+            let complete = input.len() - input.len() % 64;
+            let (tail, tail_len) =
+                Self::preprocess(&input[complete..], (input.len() as u64).wrapping_mul(8));
+
+            let mut state = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+            for chunk in input[..complete]
+                .chunks_exact(64)
+                .chain(tail[..tail_len].chunks_exact(64))
+            {
+                let block: &[u8; 64] = chunk.try_into().expect("chunks_exact gives 64 bytes");
+                state = compress(state, block);
+            }
+
+            Self::state_to_digest(state[0], state[1], state[2], state[3])
+        }
+
+        // feeds everything read from the reader until its end, using chunk as the read buffer.
+        // on_chunk is called with the total number of bytes processed after each chunk
+        #[cfg(feature = "std")]
+        fn update_from_reader<R: io::Read, F: FnMut(u64)>(
+            &mut self,
+            mut reader: R,
+            chunk: &mut [u8],
+            mut on_chunk: F,
+        ) -> io::Result<()> {
+            loop {
+                match reader.read(chunk) {
+                    Ok(0) => return Ok(()),
+                    Ok(n) => {
+                        self.update(&chunk[..n]);
+                        on_chunk(self.length);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        // one stream of blocks is always processed with scalar code: each of the 64 steps
+        // needs the result of the previous one, so simd can't speed up a single block
+        pub(crate) fn calculate_chunks(buffer: &[u8], a0: u32, b0: u32, c0: u32, d0: u32) -> u128 {
+            let mut state = [a0, b0, c0, d0];
+            for chunk in buffer.chunks_exact(64) {
+                let block: &[u8; 64] = chunk.try_into().expect("chunks_exact gives 64 bytes");
+                state = Self::compress(state, block);
+            }
+
+            Self::state_to_digest(state[0], state[1], state[2], state[3])
+        }
+
+        // Processes the 4 inputs in parallel, one in each 32 bits lane of the sse2 registers.
+        // Every step is done to all the lanes at once, and lanes whose input has no more
+        // blocks keep their state when the others move on
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        #[target_feature(enable = "sse2")]
+        pub(crate) unsafe fn calculate_x4_sse2(inputs: [&[u8]; 4]) -> [Md5Digest; 4] {
+            use core::arch::x86_64::{
+                __m128i, _mm_add_epi32, _mm_and_si128, _mm_andnot_si128, _mm_cvtsi32_si128,
+                _mm_or_si128, _mm_set1_epi32, _mm_set_epi32, _mm_sll_epi32, _mm_srl_epi32,
+                _mm_storeu_si128, _mm_xor_si128,
+            };
+
+            // the complete blocks are read straight from the inputs, only the padded last
+            // block(s) of each input are copied
+            fn block<'a>(input: &'a [u8], tail: &'a [u8; 128], k: usize) -> &'a [u8] {
+                let complete = input.len() / 64;
+                if k < complete {
+                    &input[k * 64..(k + 1) * 64]
+                } else {
+                    &tail[(k - complete) * 64..(k - complete + 1) * 64]
+                }
+            }
+
+            let tails = inputs.map(|input| {
+                Self::preprocess(
+                    &input[input.len() - input.len() % 64..],
+                    (input.len() as u64).wrapping_mul(8),
+                )
+            });
+            let n_blocks: [usize; 4] =
+                core::array::from_fn(|lane| inputs[lane].len() / 64 + tails[lane].1 / 64);
+
+            let mut state = [
+                _mm_set1_epi32(0x67452301u32 as i32),
+                _mm_set1_epi32(0xEFCDAB89u32 as i32),
+                _mm_set1_epi32(0x98BADCFEu32 as i32),
+                _mm_set1_epi32(0x10325476u32 as i32),
+            ];
+            let ones = _mm_set1_epi32(-1);
+
+            for k in 0..n_blocks.into_iter().max().unwrap() {
+                let word = |lane: usize, w: usize| -> i32 {
+                    if k < n_blocks[lane] {
+                        let b = &block(inputs[lane], &tails[lane].0, k)[w * 4..w * 4 + 4];
+                        u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as i32
+                    } else {
+                        0
+                    }
+                };
+                let m: [__m128i; 16] = core::array::from_fn(|w| {
+                    _mm_set_epi32(word(3, w), word(2, w), word(1, w), word(0, w))
+                });
+                let lane_active = |lane: usize| if k < n_blocks[lane] { -1 } else { 0 };
+                let active = _mm_set_epi32(
+                    lane_active(3),
+                    lane_active(2),
+                    lane_active(1),
+                    lane_active(0),
+                );
+
+                let [mut a, mut b, mut c, mut d] = state;
+                for i in 0..64 {
+                    let (f, g) = if i < 16 {
+                        (_mm_or_si128(_mm_and_si128(b, c), _mm_andnot_si128(b, d)), i)
+                    } else if i < 32 {
+                        (
+                            _mm_or_si128(_mm_and_si128(d, b), _mm_andnot_si128(d, c)),
+                            (5 * i + 1) % 16,
+                        )
+                    } else if i < 48 {
+                        (_mm_xor_si128(_mm_xor_si128(b, c), d), (3 * i + 5) % 16)
+                    } else {
+                        (
+                            _mm_xor_si128(c, _mm_or_si128(b, _mm_xor_si128(d, ones))),
+                            (7 * i) % 16,
+                        )
+                    };
+
+                    let f = _mm_add_epi32(
+                        _mm_add_epi32(f, a),
+                        _mm_add_epi32(m[g], _mm_set1_epi32(Self::PRECOMPUTED_TABLE[i] as i32)),
+                    );
+                    a = d;
+                    d = c;
+                    c = b;
+                    let shift = Self::SHIFT_TABLE[i] as i32;
+                    b = _mm_add_epi32(
+                        b,
+                        _mm_or_si128(
+                            _mm_sll_epi32(f, _mm_cvtsi32_si128(shift)),
+                            _mm_srl_epi32(f, _mm_cvtsi32_si128(32 - shift)),
+                        ),
+                    );
+                }
+
+                for (word, processed) in state.iter_mut().zip([a, b, c, d]) {
+                    let updated = _mm_add_epi32(*word, processed);
+                    *word = _mm_or_si128(
+                        _mm_and_si128(active, updated),
+                        _mm_andnot_si128(active, *word),
+                    );
+                }
+            }
+
+            let mut words = [[0u32; 4]; 4];
+            for (lanes, word) in words.iter_mut().zip(state) {
+                _mm_storeu_si128(lanes.as_mut_ptr() as *mut __m128i, word);
+            }
+
+            core::array::from_fn(|lane| {
+                let digest = Self::state_to_digest(
+                    words[0][lane],
+                    words[1][lane],
+                    words[2][lane],
+                    words[3][lane],
+                );
+                Md5Digest::from(digest.to_be_bytes())
+            })
+        }
+
+        // The digest is the little-endian bytes of a0, b0, c0 and d0, read as a big-endian u128
+        const fn state_to_digest(a0: u32, b0: u32, c0: u32, d0: u32) -> u128 {
+            let (a, b, c, d) = (
+                a0.to_le_bytes(),
+                b0.to_le_bytes(),
+                c0.to_le_bytes(),
+                d0.to_le_bytes(),
+            );
+            u128::from_be_bytes([
+                a[0], a[1], a[2], a[3], b[0], b[1], b[2], b[3], c[0], c[1], c[2], c[3], d[0], d[1],
+                d[2], d[3],
+            ])
+        }
+
+        // Pads the last incomplete block (less than 64 bytes) of a message. Returns the padded
+        // block(s) and how many of their bytes are used, either 64 or 128
+        pub(crate) fn preprocess(input: &[u8], original_length_in_bits: u64) -> ([u8; 128], usize) {
+            let mut preprocessed = [0u8; 128];
+            preprocessed[..input.len()].copy_from_slice(input);
+            let original_length = original_length_in_bits;
+
+            // when the length is not a multiple of 8, the last input byte is only partially
+            // part of the message, and the terminator bit goes right after its message bits
+            let partial_bits = (original_length_in_bits % 8) as u32;
+            let whole_bytes = input.len() - (partial_bits != 0) as usize;
+
+            // append bit '1' and clear the unused low bits of the partial byte. With complete
+            // bytes this is just b'10000000 == 0x80
+            let message_bits = !(0xffu8 >> partial_bits);
+            preprocessed[whole_bytes] =
+                (preprocessed[whole_bytes] & message_bits) | (0x80 >> partial_bits);
+
+            // the zeros needed to have 448 (mod 512) bits are already there, since the array
+            // starts zeroed. If the terminator byte doesn't fit before the 8 length bytes of the
+            // first block, the length goes at the end of a second one
+            let length_start = if whole_bytes < 56 { 56 } else { 120 };
+            preprocessed[length_start..length_start + 8]
+                .copy_from_slice(&original_length.to_le_bytes());
+
+            (preprocessed, length_start + 8)
+        }
+
+        // goes through Md5Digest::hex_digits, so every hex string of the crate comes from the
+        // same formatter
+        #[cfg(feature = "alloc")]
+        fn to_hex(digest: u128, case: HexCase) -> String {
+            Md5Digest::from(digest)
+                .hex_digits(case == HexCase::Upper)
+                .iter()
+                .map(|&digit| digit as char)
+                .collect()
+        }
+    }
+
+    /// The nonlinear functions of md5, one for each round of 16 steps of the compression
+    /// function, as named in rfc 1321
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum RoundFn {
+        /// `(b & c) | (!b & d)`, used in steps 0 to 15
+        F,
+        /// `(b & d) | (c & !d)`, used in steps 16 to 31
+        G,
+        /// `b ^ c ^ d`, used in steps 32 to 47
+        H,
+        /// `c ^ (b | !d)`, used in steps 48 to 63
+        I,
+    }
+
+    /// Letter case used for the hex characters of a digest
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum HexCase {
+        /// `0-9` and `a-f`
+        Lower,
+        /// `0-9` and `A-F`
+        Upper,
+    }
+
+    /// Error returned when loading a state saved with `Md5::save_state` fails
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum StateError {
+        /// The state was saved in a version this crate doesn't know
+        BadVersion,
+        /// The state is truncated or has extra bytes
+        BadLength,
+        /// The state has 64 or more buffered bytes, which never happens to a valid state
+        BadBuffer,
+    }
+
+    impl fmt::Display for StateError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                StateError::BadVersion => write!(f, "unknown md5 state version"),
+                StateError::BadLength => write!(f, "md5 state has the wrong length"),
+                StateError::BadBuffer => write!(f, "md5 state has too many buffered bytes"),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for StateError {}
+
+    /// HMAC-MD5 (RFC 2104) message authentication code calculation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::HmacMd5;
+    ///
+    /// let mut hmac = HmacMd5::new(b"Jefe");
+    /// hmac.update(b"what do ya want for nothing?");
+    /// assert_eq!(hmac.finalize().to_u128(), 0x750c783e6ab0b503eaa86e310a5db738);
+    /// ```
+    #[derive(Clone)]
+    pub struct HmacMd5 {
+        inner: Md5,
+        outer: Md5,
+    }
+
+    impl HmacMd5 {
+        /// Starts a HMAC-MD5 calculation with the key. Keys longer than 64 bytes are hashed first,
+        /// as required by the standard
+        pub fn new(key: &[u8]) -> Self {
+            let mut padded_key = [0u8; 64];
+            if key.len() > padded_key.len() {
+                padded_key[..16].copy_from_slice(&Md5::calculate_bytes(key));
+            } else {
+                padded_key[..key.len()].copy_from_slice(key);
+            }
+
+            let mut inner = Md5::new();
+            inner.update(&padded_key.map(|byte| byte ^ 0x36));
+            let mut outer = Md5::new();
+            outer.update(&padded_key.map(|byte| byte ^ 0x5c));
+
+            Self { inner, outer }
+        }
+
+        /// Feeds data to the HMAC-MD5 calculation
+        pub fn update(&mut self, data: &[u8]) {
+            self.inner.update(data);
+        }
+
+        /// Returns the HMAC-MD5 of all the data fed so far
+        pub fn finalize(self) -> Md5Digest {
+            let mut outer = self.outer;
+            outer.update(self.inner.finalize().as_bytes());
+
+            outer.finalize()
+        }
+    }
+
+    /// Returns the unix md5crypt password hash, in the `$1$salt$hash` format used by
+    /// `/etc/shadow` and `htpasswd`. Like crypt(3), only the first 8 bytes of the salt are
+    /// used, and a leading `$1$` in the salt is ignored. A non-ascii character that doesn't fit
+    /// whole in those 8 bytes is left out, so the salt in the returned string is always exactly
+    /// the salt that was hashed, and passing the returned string back as the salt gives the
+    /// same hash
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::md5crypt;
+    ///
+    /// assert_eq!(
+    ///     md5crypt(b"password", "xxxxxxxx"),
+    ///     "$1$xxxxxxxx$UYCIxa628.9qXjpQCjM4a."
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn md5crypt(password: &[u8], salt: &str) -> String {
+        const MAGIC: &str = "$1$";
+        const ALPHABET: &[u8; 64] =
+            b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+        let salt = salt.strip_prefix(MAGIC).unwrap_or(salt);
+        let mut salt_len = salt.find('$').unwrap_or(salt.len()).min(8);
+        while !salt.is_char_boundary(salt_len) {
+            salt_len -= 1;
+        }
+        let salt_str = &salt[..salt_len];
+        let salt = salt_str.as_bytes();
+
+        let alternate = Md5::new()
+            .consume_iter([password, salt, password])
+            .digest_bytes();
+
+        let mut md5 = Md5::new().consume_iter([password, MAGIC.as_bytes(), salt]);
+        for chunk in password.chunks(16) {
+            md5.update(&alternate[..chunk.len()]);
+        }
+        let mut i = password.len();
+        while i > 0 {
+            if i & 1 == 1 {
+                md5.update(&[0]);
+            } else {
+                md5.update(&password[..1]);
+            }
+            i >>= 1;
+        }
+        let mut result = md5.digest_bytes();
+
+        // the 1000 rounds are meant to make brute forcing slower
+        for i in 0..1000 {
+            let mut md5 = Md5::new();
+            if i % 2 == 1 {
+                md5.update(password);
+            } else {
+                md5.update(&result);
+            }
+            if i % 3 != 0 {
+                md5.update(salt);
+            }
+            if i % 7 != 0 {
+                md5.update(password);
+            }
+            if i % 2 == 1 {
+                md5.update(&result);
+            } else {
+                md5.update(password);
+            }
+            result = md5.digest_bytes();
+        }
+
+        let mut hash = String::with_capacity(MAGIC.len() + salt.len() + 1 + 22);
+        hash.push_str(MAGIC);
+        hash.push_str(salt_str);
+        hash.push('$');
+
+        // the bytes are encoded 3 at a time in a shuffled order, 6 bits per character
+        // starting from the least significant ones
+        let groups = [
+            (result[0], result[6], result[12], 4),
+            (result[1], result[7], result[13], 4),
+            (result[2], result[8], result[14], 4),
+            (result[3], result[9], result[15], 4),
+            (result[4], result[10], result[5], 4),
+            (0, 0, result[11], 2),
+        ];
+        for (high, middle, low, n_chars) in groups {
+            let mut value = ((high as u32) << 16) | ((middle as u32) << 8) | low as u32;
+            for _ in 0..n_chars {
+                hash.push(ALPHABET[(value & 0x3f) as usize] as char);
+                value >>= 6;
+            }
+        }
+
+        hash
+    }
+
+    /// Parser for checksum files in the format written by `md5sum`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5Sums;
+    ///
+    /// let entries = Md5Sums::parse(
+    ///     "fc5e038d38a57032085441e7fe7010b0  hello.txt\n\
+    ///      MD5 (release notes.txt) = d41d8cd98f00b204e9800998ecf8427e\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(entries[0].0, "fc5e038d38a57032085441e7fe7010b0");
+    /// assert_eq!(entries[0].1, "hello.txt");
+    /// assert_eq!(entries[1].1, "release notes.txt");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub struct Md5Sums;
+
+    #[cfg(feature = "alloc")]
+    impl Md5Sums {
+        /// Parses every line of a checksum file into its digest, filename and whether it was
+        /// hashed in binary mode
+        ///
+        /// Both the GNU format (`hash  filename`, or `hash *filename` for binary mode) and the
+        /// BSD format (`MD5 (filename) = hash`, always binary) are accepted, including the
+        /// escaped lines starting with `\` that `md5sum` writes for filenames with backslashes
+        /// or newlines. Empty lines and lines starting with `#` are skipped
+        pub fn parse(contents: &str) -> Result<Vec<(Md5Digest, String, bool)>, ParseError> {
+            let mut entries = Vec::new();
+            for (index, line) in contents.lines().enumerate() {
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                entries.push(Self::parse_line(line, index + 1)?);
+            }
+
+            Ok(entries)
+        }
+
+        fn parse_line(
+            line: &str,
+            line_number: usize,
+        ) -> Result<(Md5Digest, String, bool), ParseError> {
+            let bad_line = ParseError::BadLine(line_number);
+            let (escaped, line) = match line.strip_prefix('\\') {
+                Some(line) => (true, line),
+                None => (false, line),
+            };
+
+            let (hex, filename, binary) = if let Some(rest) = line.strip_prefix("MD5 (") {
+                let (filename, hex) = rest.rsplit_once(") = ").ok_or(bad_line)?;
+                (hex, filename, true)
+            } else {
+                let binary = match line.get(32..34) {
+                    Some("  ") => false,
+                    Some(" *") => true,
+                    _ => return Err(bad_line),
+                };
+                (&line[..32], &line[34..], binary)
+            };
+            if filename.is_empty() {
+                return Err(bad_line);
+            }
+
+            let digest = hex
+                .parse::<Md5Digest>()
+                .map_err(|_| ParseError::BadDigest(line_number))?;
+            let filename = if escaped {
+                Self::unescape(filename).ok_or(bad_line)?
+            } else {
+                String::from(filename)
+            };
+
+            Ok((digest, filename, binary))
+        }
+
+        // undoes the escaping of `\`, newline and carriage return in the filenames of md5sum
+        // lines starting with `\`
+        fn unescape(filename: &str) -> Option<String> {
+            let mut unescaped = String::with_capacity(filename.len());
+            let mut chars = filename.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next()? {
+                    '\\' => unescaped.push('\\'),
+                    'n' => unescaped.push('\n'),
+                    'r' => unescaped.push('\r'),
+                    _ => return None,
+                }
+            }
+
+            Some(unescaped)
+        }
+    }
+
+    /// Error returned when parsing a checksum file with `Md5Sums::parse` fails. Each variant has
+    /// the number of the offending line, starting at 1
+    #[cfg(feature = "alloc")]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ParseError {
+        /// The line is in neither the GNU nor the BSD md5sum format
+        BadLine(usize),
+        /// The line has a hash that is not 32 hex digits
+        BadDigest(usize),
+    }
+
+    #[cfg(feature = "alloc")]
+    impl fmt::Display for ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ParseError::BadLine(line) => write!(f, "line {} is not a md5sum line", line),
+                ParseError::BadDigest(line) => write!(f, "line {} has an invalid md5 hash", line),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for ParseError {}
+
+    /// Error returned by the fallible functions of this crate, like `Md5::try_verify` or
+    /// `Md5::load_state`, so they can all be propagated with `?` into a single type.
+    /// `Md5ParseError`, `StateError` and `io::Error` convert into it, and so do the errors of
+    /// the functions with their own error type: the `VerifyError` of `Md5::verify_file`, the
+    /// `LimitError` of `Md5::from_reader_limited` and the `ParseError` of `Md5Sums::parse`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::{Md5, Md5Digest, Md5Error};
+    ///
+    /// fn parse_and_check(hex: &str, data: &[u8]) -> Result<bool, Md5Error> {
+    ///     let expected: Md5Digest = hex.parse()?;
+    ///     Ok(Md5::calculate_digest(data) == expected)
+    /// }
+    ///
+    /// assert!(matches!(parse_and_check("fc5e", b""), Err(Md5Error::Parse(_))));
+    /// ```
+    #[derive(Debug)]
+    pub enum Md5Error {
+        /// A md5 hex string is malformed
+        Parse(Md5ParseError),
+        /// An input is longer than allowed
+        Length,
+        /// A length in bits is greater than the number of bits of the input
+        BitLength,
+        /// A saved state could not be loaded
+        State(StateError),
+        /// A checksum file could not be parsed
+        #[cfg(feature = "alloc")]
+        Manifest(ParseError),
+        /// Reading the input failed
+        #[cfg(feature = "std")]
+        Io(io::Error),
+    }
+
+    impl fmt::Display for Md5Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Md5Error::Parse(e) => write!(f, "{}", e),
+                Md5Error::Length => write!(f, "input is longer than allowed"),
+                Md5Error::BitLength => {
+                    write!(f, "bit length is greater than the length of the input")
+                }
+                Md5Error::State(e) => write!(f, "{}", e),
+                #[cfg(feature = "alloc")]
+                Md5Error::Manifest(e) => write!(f, "{}", e),
+                #[cfg(feature = "std")]
+                Md5Error::Io(e) => write!(f, "{}", e),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for Md5Error {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Md5Error::Parse(e) => Some(e),
+                Md5Error::Length | Md5Error::BitLength => None,
+                Md5Error::State(e) => Some(e),
+                Md5Error::Manifest(e) => Some(e),
+                Md5Error::Io(e) => Some(e),
+            }
+        }
+    }
+
+    impl From<Md5ParseError> for Md5Error {
+        fn from(e: Md5ParseError) -> Self {
+            Md5Error::Parse(e)
+        }
+    }
+
+    impl From<StateError> for Md5Error {
+        fn from(e: StateError) -> Self {
+            Md5Error::State(e)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl From<ParseError> for Md5Error {
+        fn from(e: ParseError) -> Self {
+            Md5Error::Manifest(e)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl From<io::Error> for Md5Error {
+        fn from(e: io::Error) -> Self {
+            Md5Error::Io(e)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl From<VerifyError> for Md5Error {
+        fn from(e: VerifyError) -> Self {
+            match e {
+                VerifyError::Io(e) => Md5Error::Io(e),
+                VerifyError::Parse(e) => Md5Error::Parse(e),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl From<LimitError> for Md5Error {
+        fn from(e: LimitError) -> Self {
+            match e {
+                LimitError::Exceeded => Md5Error::Length,
+                LimitError::Io(e) => Md5Error::Io(e),
+            }
+        }
+    }
+
+    /// Error returned when parsing a hex string into a `Md5Digest` fails
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Md5ParseError {
+        /// The string does not have exactly 32 characters
+        BadLength,
+        /// The string has a character that is not a hex digit
+        BadChar,
+    }
+
+    impl fmt::Display for Md5ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Md5ParseError::BadLength => write!(f, "md5 hex string must have 32 characters"),
+                Md5ParseError::BadChar => write!(f, "md5 hex string has a non-hex character"),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for Md5ParseError {}
+
+    /// Error returned by `Md5::verify_file`
+    #[cfg(feature = "std")]
+    #[derive(Debug)]
+    pub enum VerifyError {
+        /// The file could not be read
+        Io(io::Error),
+        /// The expected hash is not a valid md5 hex string
+        Parse(Md5ParseError),
+    }
+
+    #[cfg(feature = "std")]
+    impl fmt::Display for VerifyError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                VerifyError::Io(e) => write!(f, "could not read the file: {}", e),
+                VerifyError::Parse(e) => write!(f, "invalid expected hash: {}", e),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for VerifyError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                VerifyError::Io(e) => Some(e),
+                VerifyError::Parse(e) => Some(e),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl From<io::Error> for VerifyError {
+        fn from(e: io::Error) -> Self {
+            VerifyError::Io(e)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl From<Md5ParseError> for VerifyError {
+        fn from(e: Md5ParseError) -> Self {
+            VerifyError::Parse(e)
+        }
+    }
+
+    /// Error returned by `Md5::from_reader_limited`
+    #[cfg(feature = "std")]
+    #[derive(Debug)]
+    pub enum LimitError {
+        /// The reader has more bytes than the limit
+        Exceeded,
+        /// Reading failed
+        Io(io::Error),
+    }
+
+    #[cfg(feature = "std")]
+    impl fmt::Display for LimitError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                LimitError::Exceeded => write!(f, "input is longer than the limit"),
+                LimitError::Io(e) => write!(f, "could not read the input: {}", e),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for LimitError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                LimitError::Exceeded => None,
+                LimitError::Io(e) => Some(e),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl From<io::Error> for LimitError {
+        fn from(e: io::Error) -> Self {
+            LimitError::Io(e)
+        }
+    }
+
+    /// Configures how a reader is hashed, gathering the options of the `from_reader_*`
+    /// functions in one place. Nothing is set by default: the data is read in chunks of
+    /// `Md5::DEFAULT_CHUNK_SIZE` bytes, with no progress callback and no limit
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5Builder;
+    /// use std::io::Cursor;
+    ///
+    /// let mut read = 0;
+    /// let digest = Md5Builder::new()
+    ///     .chunk_size(4)
+    ///     .progress(|n| read = n)
+    ///     .limit(1024)
+    ///     .hash_reader(Cursor::new(b"helloworld"))
+    ///     .unwrap();
+    /// assert_eq!(digest, "fc5e038d38a57032085441e7fe7010b0");
+    /// assert_eq!(read, 10);
+    /// ```
+    #[cfg(feature = "std")]
+    pub struct Md5Builder<F = fn(u64)> {
+        chunk_size: usize,
+        progress: F,
+        limit: u64,
+    }
+
+    #[cfg(feature = "std")]
+    impl Md5Builder {
+        /// Starts a configuration with every option at its default
+        pub fn new() -> Self {
+            Self {
+                chunk_size: Md5::DEFAULT_CHUNK_SIZE,
+                progress: |_| {},
+                limit: u64::MAX,
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for Md5Builder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<F: FnMut(u64)> Md5Builder<F> {
+        /// Sets how many bytes are read at a time, like `Md5::from_reader_with_capacity`
+        pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+            self.chunk_size = chunk_size;
+            self
+        }
+
+        /// Sets a callback called with the total number of bytes read so far after each chunk,
+        /// like `Md5::from_reader_with_progress`. For an empty input it is called once with 0
+        pub fn progress<G: FnMut(u64)>(self, progress: G) -> Md5Builder<G> {
+            Md5Builder {
+                chunk_size: self.chunk_size,
+                progress,
+                limit: self.limit,
+            }
+        }
+
+        /// Sets the maximum number of bytes the reader may have, like
+        /// `Md5::from_reader_limited`
+        pub fn limit(mut self, max_bytes: u64) -> Self {
+            self.limit = max_bytes;
+            self
+        }
+
+        /// Returns the md5 hash of everything read from the reader until its end, with the
+        /// configured options. A chunk size of 0 is an `io::ErrorKind::InvalidInput` error
+        pub fn hash_reader<R: io::Read>(mut self, mut reader: R) -> Result<Md5Digest, LimitError> {
+            if self.chunk_size == 0 {
+                return Err(LimitError::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "chunk size must be greater than 0",
+                )));
+            }
+
+            let mut md5 = Md5::new();
+            let mut chunk = vec![0u8; self.chunk_size];
+            loop {
+                // asking for one byte more than what is left is enough to tell if the reader
+                // goes past the limit
+                let left = self.limit - md5.length;
+                let len = chunk
+                    .len()
+                    .min(usize::try_from(left.saturating_add(1)).unwrap_or(usize::MAX));
+                match reader.read(&mut chunk[..len]) {
+                    Ok(0) => break,
+                    Ok(n) if n as u64 > left => return Err(LimitError::Exceeded),
+                    Ok(n) => {
+                        md5.update(&chunk[..n]);
+                        (self.progress)(md5.length);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(LimitError::Io(e)),
+                }
+            }
+            if md5.length == 0 {
+                (self.progress)(0);
+            }
+
+            Ok(md5.finalize())
+        }
+    }
+
+    /// Wraps a reader so that the bytes are hashed as they are read through it, which gives
+    /// the md5 of exactly the bytes the consumer of the reader actually took
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::{HashingReader, Md5};
+    /// use std::io::Read;
+    ///
+    /// let mut reader = HashingReader::new(&b"helloworld"[..]);
+    /// let mut hello = [0u8; 5];
+    /// reader.read_exact(&mut hello).unwrap();
+    /// assert_eq!(reader.digest(), Md5::calculate_digest(b"hello"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub struct HashingReader<R> {
+        inner: R,
+        md5: Md5,
+    }
+
+    #[cfg(feature = "std")]
+    impl<R: io::Read> HashingReader<R> {
+        /// Wraps the reader, with nothing hashed yet
+        pub fn new(inner: R) -> Self {
+            Self {
+                inner,
+                md5: Md5::new(),
+            }
+        }
+
+        /// Returns the md5 hash of all the bytes read through the adapter
+        pub fn digest(self) -> Md5Digest {
+            self.md5.finalize()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<R: io::Read> io::Read for HashingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.md5.update(&buf[..n]);
+
+            Ok(n)
+        }
+    }
+
+    // the state of a Md5 as it is serialized, with only the bytes actually buffered
+    #[cfg(feature = "serde")]
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct SerializedMd5 {
+        buffer: Vec<u8>,
+        length: u64,
+        a0: u32,
+        b0: u32,
+        c0: u32,
+        d0: u32,
+    }
+
+    #[cfg(feature = "serde")]
+    impl From<Md5> for SerializedMd5 {
+        fn from(md5: Md5) -> Self {
+            Self {
+                buffer: md5.buffer[..md5.buffer_len].to_vec(),
+                length: md5.length,
+                a0: md5.a0,
+                b0: md5.b0,
+                c0: md5.c0,
+                d0: md5.d0,
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl TryFrom<SerializedMd5> for Md5 {
+        type Error = &'static str;
+
+        fn try_from(serialized: SerializedMd5) -> Result<Self, Self::Error> {
+            if serialized.buffer.len() >= 64 {
+                return Err("md5 buffer must have less than 64 bytes");
+            }
+
+            let mut md5 = Md5::from_state(
+                serialized.a0,
+                serialized.b0,
+                serialized.c0,
+                serialized.d0,
+                serialized.length,
+            );
+            md5.buffer[..serialized.buffer.len()].copy_from_slice(&serialized.buffer);
+            md5.buffer_len = serialized.buffer.len();
+
+            Ok(md5)
+        }
+    }
+
+    /// With the `zeroize` feature, the buffered data and the state are wiped from memory when
+    /// the object is dropped, which includes after `finalize`
+    #[cfg(feature = "zeroize")]
+    impl Drop for Md5 {
+        fn drop(&mut self) {
+            self.wipe();
+        }
+    }
+
+    impl Default for Md5 {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Compares the running states: the four words, the total length and the buffered bytes.
+    /// The rest of the buffer holds leftovers of previous blocks, so it is ignored
+    ///
+    /// Two objects fed the same data from the same starting point are always equal, however the
+    /// data was split, since the buffer always holds the bytes after the last complete block.
+    /// They differ if they started from different states (for example with `with_iv`), even
+    /// when they end up with the same digest
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let whole = Md5::new().consume(b"helloworld");
+    /// let pieces = Md5::new().consume(b"hello").consume(b"world");
+    /// assert!(whole == pieces);
+    /// assert!(whole != Md5::new());
+    /// ```
+    impl PartialEq for Md5 {
+        fn eq(&self, other: &Self) -> bool {
+            self.length == other.length
+                && self.chaining_words() == other.chaining_words()
+                && self.buffer[..self.buffer_len] == other.buffer[..other.buffer_len]
+        }
+    }
+
+    impl Eq for Md5 {}
+
+    /// Feeds the written bytes to the md5 calculation, so a `Md5` can be used with `io::copy`
+    ///
+    /// `&mut Md5` is a writer too, through the `impl Write for &mut W` of the standard library,
+    /// so a borrowed `Md5` can be passed to functions taking a `W: Write` by value
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    /// use std::io;
+    ///
+    /// let mut md5 = Md5::new();
+    /// io::copy(&mut &b"helloworld"[..], &mut md5).unwrap();
+    /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+    /// ```
+    #[cfg(feature = "std")]
+    impl io::Write for Md5 {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.update(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// With the `async` feature, the written bytes are fed to the md5 calculation, so a `Md5`
+    /// can be used with `tokio::io::copy`. Hashing never blocks, so every call is ready at once
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "async")]
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let mut md5 = Md5::new();
+    /// tokio::io::copy(&mut &b"helloworld"[..], &mut md5).await.unwrap();
+    /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    impl tokio::io::AsyncWrite for Md5 {
+        fn poll_write(
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+            buf: &[u8],
+        ) -> core::task::Poll<io::Result<usize>> {
+            self.get_mut().update(buf);
+
+            core::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<io::Result<()>> {
+            core::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: core::pin::Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<io::Result<()>> {
+            core::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Feeds the bytes of an iterator to the md5 calculation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let mut md5 = Md5::new();
+    /// md5.extend(b"helloworld".iter().copied());
+    /// assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+    /// ```
+    impl Extend<u8> for Md5 {
+        fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+            // gather the bytes into blocks, so update is not called for every single byte
+            let mut block = [0u8; 64];
+            let mut filled = 0;
+            for byte in iter {
+                block[filled] = byte;
+                filled += 1;
+                if filled == block.len() {
+                    self.update(&block);
+                    filled = 0;
+                }
+            }
+
+            self.update(&block[..filled]);
+        }
+    }
+
+    impl<'a> Extend<&'a u8> for Md5 {
+        fn extend<I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+            self.extend(iter.into_iter().copied());
+        }
+    }
+
+    /// Allows using md5 for non-cryptographic hashing, e.g. the keys of a `HashMap`.
+    /// `finish` returns the low 64 bits of the digest, i.e. its last 8 bytes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    /// use std::collections::HashMap;
+    /// use std::hash::BuildHasherDefault;
+    ///
+    /// let mut map: HashMap<&str, u32, BuildHasherDefault<Md5>> = HashMap::default();
+    /// map.insert("hello", 1);
+    /// assert_eq!(map.get("hello"), Some(&1));
+    /// ```
+    impl Hasher for Md5 {
+        fn write(&mut self, bytes: &[u8]) {
+            self.update(bytes);
+        }
+
+        fn finish(&self) -> u64 {
+            self.digest() as u64
+        }
+    }
+
+    /// With the `digest-traits` feature, `Md5` implements the RustCrypto traits, so it can be
+    /// used by generic code expecting a `digest::Digest`. `Md5` has inherent methods with the
+    /// same names as some of the trait methods, so those must be called through the trait
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "digest-traits")]
+    /// # {
+    /// use digest::Digest;
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let output = Digest::finalize(Md5::new().chain_update(b"hello").chain_update(b"world"));
+    /// assert_eq!(output[..], Md5::calculate_bytes(b"helloworld"));
+    /// # }
+    /// ```
+    #[cfg(feature = "digest-traits")]
+    impl digest::HashMarker for Md5 {}
+
+    #[cfg(feature = "digest-traits")]
+    impl digest::OutputSizeUser for Md5 {
+        type OutputSize = digest::consts::U16;
+    }
+
+    #[cfg(feature = "digest-traits")]
+    impl digest::Update for Md5 {
+        fn update(&mut self, data: &[u8]) {
+            Md5::update(self, data);
+        }
+    }
+
+    #[cfg(feature = "digest-traits")]
+    impl digest::FixedOutput for Md5 {
+        fn finalize_into(self, out: &mut digest::Output<Self>) {
+            out.copy_from_slice(&self.digest_bytes());
+        }
+    }
+
+    #[cfg(feature = "digest-traits")]
+    impl digest::Reset for Md5 {
+        fn reset(&mut self) {
+            Md5::reset(self);
+        }
+    }
+
+    /// The result of a md5 calculation
+    ///
+    /// The 16 bytes are stored in the standard md5 order (the same order `md5sum` prints them).
+    /// Digests are ordered by those bytes, which is also the order of their hex strings. `==` is
+    /// not constant time, use `ct_eq` to compare against secret digests
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Md5Digest([u8; 16]);
+
+    impl Md5Digest {
+        /// Returns the bytes of the digest in the standard md5 order
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(digest.as_bytes()[..4], [0xfc, 0x5e, 0x03, 0x8d]);
+        /// ```
+        pub fn as_bytes(&self) -> &[u8; 16] {
+            &self.0
+        }
+
+        /// Returns the digest as the same u128 returned by `Md5::digest` and `Md5::calculate`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        /// ```
+        pub fn to_u128(&self) -> u128 {
+            u128::from_be_bytes(self.0)
+        }
+
+        /// Returns the bytes of the digest in big-endian order. This is the canonical RFC 1321
+        /// output, the same order `md5sum` prints them and the same as `as_bytes`
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(digest.to_be_bytes(), digest.to_u128().to_be_bytes());
+        /// ```
+        pub fn to_be_bytes(&self) -> [u8; 16] {
+            self.0
+        }
+
+        /// Returns the first `N` bytes of the digest, in the standard md5 order
+        ///
+        /// Truncating weakens the hash: collisions among `N` byte prefixes are found after about
+        /// `2^(4 * N)` attempts instead of `2^64`, so only use short prefixes where collisions
+        /// are tolerable, like cache keys or short content ids. An `N` greater than 16 doesn't
+        /// compile
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(digest.truncated::<4>(), [0xfc, 0x5e, 0x03, 0x8d]);
+        /// ```
+        ///
+        /// ```compile_fail
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let too_long: [u8; 17] = Md5::calculate_digest(b"helloworld").truncated::<17>();
+        /// ```
+        pub fn truncated<const N: usize>(&self) -> [u8; N] {
+            const { assert!(N <= 16, "a md5 digest only has 16 bytes") };
+
+            core::array::from_fn(|i| self.0[i])
+        }
+
+        /// Returns the high and low 64 bits of the digest xored together, for bucketing in hash
+        /// tables. Unlike truncating, every bit of the digest affects the result
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(digest.fold_u64(), 0xfc5e038d38a57032 ^ 0x085441e7fe7010b0);
+        /// ```
+        pub fn fold_u64(&self) -> u64 {
+            let digest = self.to_u128();
+
+            ((digest >> 64) as u64) ^ (digest as u64)
+        }
+
+        /// Returns the bytes of the digest in little-endian order, which is the reverse of the
+        /// canonical RFC 1321 output
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(digest.to_le_bytes(), digest.to_u128().to_le_bytes());
+        /// ```
+        pub fn to_le_bytes(&self) -> [u8; 16] {
+            let mut bytes = self.0;
+            bytes.reverse();
+
+            bytes
+        }
+
+        /// Compares two digests in constant time, so the time taken doesn't reveal how many
+        /// leading bytes match. Use this instead of `==` when comparing against a value
+        /// supplied by an attacker
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert!(digest.ct_eq(&Md5::calculate_digest(b"helloworld")));
+        /// assert!(!digest.ct_eq(&Md5::calculate_digest(b"hello")));
+        /// ```
+        pub fn ct_eq(&self, other: &Md5Digest) -> bool {
+            // accumulate the differences of every byte without returning early. black_box keeps
+            // the compiler from turning the loop back into a short-circuiting comparison
+            let mut difference = 0u8;
+            for (a, b) in self.0.iter().zip(other.0.iter()) {
+                difference |= core::hint::black_box(a ^ b);
+            }
+
+            core::hint::black_box(difference) == 0
+        }
+
+        /// Returns the digest encoded as standard padded base64, as expected by the
+        /// `Content-MD5` http header
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"");
+        /// assert_eq!(digest.to_base64(), "1B2M2Y8AsgTpgAmY7PhCfg==");
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn to_base64(&self) -> String {
+            const ALPHABET: &[u8; 64] =
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+            // 16 bytes are 5 complete groups of 3 bytes plus a single remaining byte,
+            // so the output is always 20 + 2 characters followed by "=="
+            let mut encoded = String::with_capacity(24);
+            for group in self.0.chunks(3) {
+                let b0 = group[0] as usize;
+                let b1 = group.get(1).copied().unwrap_or(0) as usize;
+                let b2 = group.get(2).copied().unwrap_or(0) as usize;
+
+                encoded.push(ALPHABET[b0 >> 2] as char);
+                encoded.push(ALPHABET[((b0 & 0x03) << 4) | (b1 >> 4)] as char);
+                if group.len() == 3 {
+                    encoded.push(ALPHABET[((b1 & 0x0f) << 2) | (b2 >> 6)] as char);
+                    encoded.push(ALPHABET[b2 & 0x3f] as char);
+                }
+            }
+            encoded.push_str("==");
+
+            encoded
+        }
+
+        /// Returns the line `md5sum` prints for a file with this digest, without the trailing
+        /// newline: the hex digest, then two spaces (text mode) or ` *` (binary mode), then the
+        /// filename. Like `md5sum`, a filename with backslashes, newlines or carriage returns is
+        /// escaped and the line starts with `\`, so `Md5Sums::parse` reads it back unchanged
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(
+        ///     digest.to_md5sum_line("hello.txt", false),
+        ///     "fc5e038d38a57032085441e7fe7010b0  hello.txt"
+        /// );
+        /// assert_eq!(
+        ///     digest.to_md5sum_line("a\\b", true),
+        ///     "\\fc5e038d38a57032085441e7fe7010b0 *a\\\\b"
+        /// );
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn to_md5sum_line(&self, filename: &str, binary: bool) -> String {
+            let escaped = filename.contains(['\\', '\n', '\r']);
+
+            let mut line = String::with_capacity(filename.len() + 36);
+            if escaped {
+                line.push('\\');
+            }
+            for &digit in &self.hex_digits(false) {
+                line.push(digit as char);
+            }
+            line.push_str(if binary { " *" } else { "  " });
+            if escaped {
+                for c in filename.chars() {
+                    match c {
+                        '\\' => line.push_str("\\\\"),
+                        '\n' => line.push_str("\\n"),
+                        '\r' => line.push_str("\\r"),
+                        c => line.push(c),
+                    }
+                }
+            } else {
+                line.push_str(filename);
+            }
+
+            line
+        }
+
+        /// Returns the 32 lowercase hex characters of the digest in groups of `group`
+        /// characters, separated by `sep`, the way fingerprints are often displayed. When
+        /// `group` doesn't divide 32, the last group is shorter, and a `group` of 32 or more
+        /// gives the plain hex string
+        ///
+        /// # Panics
+        ///
+        /// Panics if `group` is 0
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let digest = Md5::calculate_digest(b"helloworld");
+        /// assert_eq!(
+        ///     digest.to_grouped_hex(4, ':'),
+        ///     "fc5e:038d:38a5:7032:0854:41e7:fe70:10b0"
+        /// );
+        /// ```
+        #[cfg(feature = "alloc")]
+        pub fn to_grouped_hex(&self, group: usize, sep: char) -> String {
+            assert!(group > 0, "the group size must not be 0");
+
+            let digits = self.hex_digits(false);
+            let mut grouped = String::with_capacity(32 + (32 / group) * sep.len_utf8());
+            for (i, chunk) in digits.chunks(group).enumerate() {
+                if i > 0 {
+                    grouped.push(sep);
+                }
+                grouped.extend(chunk.iter().map(|&digit| digit as char));
+            }
+
+            grouped
+        }
+
+        /// Writes the 32 lowercase ascii hex characters of the digest into `out`. Nothing is
+        /// allocated, so unlike `to_string` it works without the `alloc` feature
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut hex = [0u8; 32];
+        /// Md5::calculate_digest(b"helloworld").write_hex(&mut hex);
+        /// assert_eq!(&hex, b"fc5e038d38a57032085441e7fe7010b0");
+        /// ```
+        pub fn write_hex(&self, out: &mut [u8; 32]) {
+            *out = self.hex_digits(false);
+        }
+
+        /// Same as `write_hex`, but with uppercase hex characters
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use md5_core::md5_core::Md5;
+        ///
+        /// let mut hex = [0u8; 32];
+        /// Md5::calculate_digest(b"helloworld").write_hex_upper(&mut hex);
+        /// assert_eq!(&hex, b"FC5E038D38A57032085441E7FE7010B0");
+        /// ```
+        pub fn write_hex_upper(&self, out: &mut [u8; 32]) {
+            *out = self.hex_digits(true);
+        }
+
+        // the 32 ascii hex characters of the digest, formatted without allocating
+        fn hex_digits(&self, uppercase: bool) -> [u8; 32] {
+            let alphabet = if uppercase {
+                b"0123456789ABCDEF"
+            } else {
+                b"0123456789abcdef"
+            };
+
+            let mut digits = [0u8; 32];
+            for (i, byte) in self.0.iter().enumerate() {
+                digits[2 * i] = alphabet[(byte >> 4) as usize];
+                digits[2 * i + 1] = alphabet[(byte & 0x0f) as usize];
+            }
+
+            digits
+        }
+
+        fn hex_value(c: u8) -> Result<u8, Md5ParseError> {
+            match c {
+                b'0'..=b'9' => Ok(c - b'0'),
+                b'a'..=b'f' => Ok(c - b'a' + 10),
+                b'A'..=b'F' => Ok(c - b'A' + 10),
+                _ => Err(Md5ParseError::BadChar),
+            }
+        }
+    }
+
+    /// Formats the digest as a 32 characters lowercase hex string
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let digest = Md5::calculate_digest(b"");
+    /// assert_eq!(digest.to_string(), "d41d8cd98f00b204e9800998ecf8427e");
+    /// ```
+    impl fmt::Display for Md5Digest {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::LowerHex::fmt(self, f)
+        }
+    }
+
+    /// Formats the digest as 32 lowercase hex characters. Width and fill flags are honored, and
+    /// the `#` flag adds no prefix, since md5 has no conventional one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let digest = Md5::calculate_digest(b"helloworld");
+    /// assert_eq!(format!("{:x}", digest), "fc5e038d38a57032085441e7fe7010b0");
+    /// ```
+    impl fmt::LowerHex for Md5Digest {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let digits = self.hex_digits(false);
+            f.pad(core::str::from_utf8(&digits).map_err(|_| fmt::Error)?)
+        }
+    }
+
+    /// Formats the digest as 32 uppercase hex characters. Width and fill flags are honored, and
+    /// the `#` flag adds no prefix, since md5 has no conventional one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let digest = Md5::calculate_digest(b"helloworld");
+    /// assert_eq!(format!("{:X}", digest), "FC5E038D38A57032085441E7FE7010B0");
+    /// ```
+    impl fmt::UpperHex for Md5Digest {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let digits = self.hex_digits(true);
+            f.pad(core::str::from_utf8(&digits).map_err(|_| fmt::Error)?)
+        }
+    }
+
+    /// Parses a 32 characters hex string (either lowercase or uppercase) into a digest
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::{Md5, Md5Digest};
+    ///
+    /// let digest: Md5Digest = "FC5E038D38A57032085441E7FE7010B0".parse().unwrap();
+    /// assert_eq!(digest, Md5::calculate_digest(b"helloworld"));
+    /// ```
+    impl FromStr for Md5Digest {
+        type Err = Md5ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = s.as_bytes();
+            if s.len() != 32 {
+                return Err(Md5ParseError::BadLength);
+            }
+
+            let mut bytes = [0u8; 16];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = (Self::hex_value(s[2 * i])? << 4) | Self::hex_value(s[2 * i + 1])?;
+            }
+
+            Ok(Self(bytes))
+        }
+    }
+
+    /// Compares the digest against a hex string (either lowercase or uppercase). A malformed
+    /// string is never equal to a digest
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::Md5;
+    ///
+    /// let digest = Md5::calculate_digest(b"helloworld");
+    /// assert!(digest == "fc5e038d38a57032085441e7fe7010b0");
+    /// ```
+    impl PartialEq<str> for Md5Digest {
+        fn eq(&self, other: &str) -> bool {
+            other.parse::<Md5Digest>() == Ok(*self)
+        }
+    }
+
+    impl PartialEq<&str> for Md5Digest {
+        fn eq(&self, other: &&str) -> bool {
+            self == *other
+        }
+    }
+
+    /// Builds a digest from its bytes in the standard md5 order
+    impl From<[u8; 16]> for Md5Digest {
+        fn from(bytes: [u8; 16]) -> Self {
+            Self(bytes)
+        }
+    }
+
+    /// Builds a digest from the u128 returned by `Md5::digest` and `Md5::calculate`, whose
+    /// big-endian bytes are the bytes of the digest
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use md5_core::md5_core::{Md5, Md5Digest};
+    ///
+    /// let digest = Md5Digest::from(Md5::calculate(b"helloworld"));
+    /// assert_eq!(digest, Md5::calculate_digest(b"helloworld"));
+    /// ```
+    impl From<u128> for Md5Digest {
+        fn from(digest: u128) -> Self {
+            Self(digest.to_be_bytes())
+        }
+    }
+
+    /// Same as `Md5Digest::to_u128`
+    impl From<Md5Digest> for u128 {
+        fn from(digest: Md5Digest) -> Self {
+            digest.to_u128()
+        }
+    }
+
+    /// Same as `Md5Digest::to_be_bytes`
+    impl From<Md5Digest> for [u8; 16] {
+        fn from(digest: Md5Digest) -> Self {
+            digest.0
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use crate::md5_core;
+    use std::prelude::rust_2021::*;
+
+    use md5_core::{HmacMd5, Md5, Md5Digest, Md5ParseError};
+
+    // the test suite of RFC 1321 appendix A.5, shared by the tests of every code path
+    const RFC_1321_VECTORS: [(&[u8], u128); 7] = [
+        (b"", 0xd41d8cd98f00b204e9800998ecf8427e),
+        (b"a", 0x0cc175b9c0f1b6a831c399e269772661),
+        (b"abc", 0x900150983cd24fb0d6963f7d28e17f72),
+        (b"message digest", 0xf96b697d7cb7938d525a2f31aaf161d0),
+        (
+            b"abcdefghijklmnopqrstuvwxyz",
+            0xc3fcd3d76192e4007dfb496cca67e13b,
+        ),
+        (
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            0xd174ab98d277d9f5a5611c2c9f419d9f,
+        ),
+        (
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            0x57edf4a22be3c955ac49da2e2107b67a,
+        ),
+    ];
+
+    // `len` bytes of test data. 251 is prime, so the pattern doesn't repeat with the 64 byte blocks
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    mod rfc_1321 {
+        use super::RFC_1321_VECTORS;
+        use crate::md5_core::Md5;
+
+        #[test]
+        fn calculate() {
+            for (input, expected) in RFC_1321_VECTORS {
+                assert_eq!(Md5::calculate(input), expected);
+            }
+        }
+
+        #[test]
+        fn calculate_const() {
+            const DIGESTS: [u128; 7] = {
+                let mut digests = [0; 7];
+                let mut i = 0;
+                while i < 7 {
+                    digests[i] = Md5::calculate_const(RFC_1321_VECTORS[i].0);
+                    i += 1;
+                }
+                digests
+            };
+
+            for ((_, expected), digest) in RFC_1321_VECTORS.iter().zip(DIGESTS) {
+                assert_eq!(digest, *expected);
+            }
+        }
+
+        #[test]
+        fn update_one_byte_at_a_time() {
+            for (input, expected) in RFC_1321_VECTORS {
+                let mut md5 = Md5::new();
+                for byte in input {
+                    md5.update(&[*byte]);
+                }
+                assert_eq!(md5.digest(), expected);
+            }
+        }
+
+        #[test]
+        fn calculate_x4() {
+            for group in RFC_1321_VECTORS.windows(4) {
+                let digests = Md5::calculate_x4([group[0].0, group[1].0, group[2].0, group[3].0]);
+                for (digest, (_, expected)) in digests.iter().zip(group) {
+                    assert_eq!(digest.to_u128(), *expected);
+                }
+            }
+        }
+
+        #[test]
+        fn calculate_bits() {
+            for (input, expected) in RFC_1321_VECTORS {
+                assert_eq!(Md5::calculate_bits(input, input.len() as u64 * 8), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn calculate_from_empty_returns_0xd41d8cd98f00b204e9800998ecf8427e() {
+        assert_eq!(Md5::calculate(b""), 0xd41d8cd98f00b204e9800998ecf8427e);
+    }
+
+    #[test]
+    fn calculate_from_helloworld_returns_0xfc5e038d38a57032085441e7fe7010b0() {
+        assert_eq!(
+            Md5::calculate(b"helloworld"),
+            0xfc5e038d38a57032085441e7fe7010b0
+        );
+    }
+
+    #[test]
+    fn calculate_from_448_bits_() {
+        assert_eq!(
+            Md5::calculate(b"Lorem ipsum dolor sit amet, consectetur adipiscing odio."),
+            0x2251013dde7bffaa1780cf66fbbaf4bb
+        );
+    }
+
+    #[test]
+    fn calculate_from_two_chunks() {
+        assert_eq!(
+            Md5::calculate(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis. Nullam consectetur nisi non nibh posuere suscipit. Nam velit est, fringilla tincidunt eleifend nec, cursus sit amet metus. Suspendisse id lacus at risus sollicitudin volutpat id in urna. Pellentesque commodo iaculis lectus vitae pulvinar. Morbi ullamcorper ex nisl. Vivamus vel fringilla metus, sit amet malesuada justo. Fusce in lobortis velit. Mauris sed purus mauris. Aenean lobortis bibendum ex quis congue. Etiam sapien nulla, viverra ut lorem blandit."),
+            0xba5e84b5ac5785cca9f18469cc8e0193
+        );
+    }
+
+    #[test]
+    fn consume_empty_and_digest() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"");
+        assert_eq!(md5.digest(), 0xd41d8cd98f00b204e9800998ecf8427e);
+    }
+
+    #[test]
+    fn consume_twice_small_and_digest() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"hello");
+        md5 = md5.consume(b"world");
+        assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+    }
+
+    #[test]
+    fn consume_twice_two_chunks_and_digest() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.");
+        md5 = md5.consume(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.");
+        assert_eq!(md5.digest(), 0xce13701da5de58af48900b63f2da47ca);
+    }
+
+    #[test]
+    fn calculate_bytes_from_helloworld() {
+        assert_eq!(
+            Md5::calculate_bytes(b"helloworld"),
+            [
+                0xfc, 0x5e, 0x03, 0x8d, 0x38, 0xa5, 0x70, 0x32, 0x08, 0x54, 0x41, 0xe7, 0xfe, 0x70,
+                0x10, 0xb0
+            ]
+        );
+    }
+
+    #[test]
+    fn consume_and_digest_bytes() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"hello");
+        md5 = md5.consume(b"world");
+        assert_eq!(md5.digest_bytes(), Md5::calculate_bytes(b"helloworld"));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_hex_from_empty() {
+        assert_eq!(Md5::calculate_hex(b""), "d41d8cd98f00b204e9800998ecf8427e");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_hex_keeps_leading_zeros() {
+        assert_eq!(
+            Md5::calculate_hex(b"168"),
+            "006f52e9102a8d3be2fe5614f42ba989"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn consume_and_digest_hex() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"hello");
+        md5 = md5.consume(b"world");
+        assert_eq!(md5.digest_hex(), "fc5e038d38a57032085441e7fe7010b0");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_hex_upper_from_empty() {
+        assert_eq!(
+            Md5::calculate_hex_upper(b""),
+            "D41D8CD98F00B204E9800998ECF8427E"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_hex_upper_keeps_leading_zeros() {
+        assert_eq!(
+            Md5::calculate_hex_upper(b"168"),
+            "006F52E9102A8D3BE2FE5614F42BA989"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn digest_hex_and_digest_hex_upper_differ_only_in_case() {
+        let md5 = Md5::new().consume(b"helloworld");
+        assert_eq!(md5.digest_hex_upper(), md5.digest_hex().to_uppercase());
+        assert_eq!(md5.digest_hex(), md5.digest_hex_upper().to_lowercase());
+    }
+
+    #[test]
+    fn calculate_digest_from_helloworld() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert_eq!(digest.to_u128(), 0xfc5e038d38a57032085441e7fe7010b0);
+        assert_eq!(digest.as_bytes(), &Md5::calculate_bytes(b"helloworld"));
+    }
+
+    #[test]
+    fn consume_and_to_digest() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"hello");
+        md5 = md5.consume(b"world");
+        assert_eq!(md5.to_digest(), Md5::calculate_digest(b"helloworld"));
+    }
+
+    #[test]
+    fn digest_from_bytes() {
+        let bytes = Md5::calculate_bytes(b"helloworld");
+        assert_eq!(Md5Digest::from(bytes), Md5::calculate_digest(b"helloworld"));
+    }
+
+    #[test]
+    fn display_digest_keeps_leading_zeros() {
+        assert_eq!(
+            Md5::calculate_digest(b"168").to_string(),
+            "006f52e9102a8d3be2fe5614f42ba989"
+        );
+    }
+
+    #[test]
+    fn parse_digest_from_uppercase_hex() {
+        assert_eq!(
+            "FC5E038D38A57032085441E7FE7010B0".parse::<Md5Digest>(),
+            Ok(Md5::calculate_digest(b"helloworld"))
+        );
+    }
+
+    #[test]
+    fn parse_digest_from_too_short_hex() {
+        assert_eq!(
+            "fc5e038d38a57032085441e7fe7010b".parse::<Md5Digest>(),
+            Err(Md5ParseError::BadLength)
+        );
+    }
+
+    #[test]
+    fn parse_digest_from_non_hex_character() {
+        assert_eq!(
+            "fc5e038d38a57032085441e7fe7010bg".parse::<Md5Digest>(),
+            Err(Md5ParseError::BadChar)
+        );
+    }
+
+    #[test]
+    fn display_and_parse_digest_round_trip() {
+        let digest = Md5::calculate_digest(b"168");
+        assert_eq!(digest.to_string().parse::<Md5Digest>(), Ok(digest));
+    }
+
+    #[test]
+    fn format_digest_as_lower_and_upper_hex() {
+        let digest = Md5Digest::from([
+            0x00, 0x01, 0x0f, 0x10, 0x7f, 0x80, 0x9a, 0xab, 0xbc, 0xcd, 0xde, 0xef, 0xf0, 0xfe,
+            0x0a, 0xff,
+        ]);
+        assert_eq!(format!("{:x}", digest), "00010f107f809aabbccddeeff0fe0aff");
+        assert_eq!(format!("{:X}", digest), "00010F107F809AABBCCDDEEFF0FE0AFF");
+        assert_eq!(format!("{:#x}", digest), "00010f107f809aabbccddeeff0fe0aff");
+    }
+
+    #[test]
+    fn format_digest_as_hex_with_width_and_fill() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert_eq!(
+            format!("{:>34x}", digest),
+            "  fc5e038d38a57032085441e7fe7010b0"
+        );
+        assert_eq!(
+            format!("{:*<34X}", digest),
+            "FC5E038D38A57032085441E7FE7010B0**"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn digest_to_base64() {
+        assert_eq!(
+            Md5::calculate_digest(b"").to_base64(),
+            "1B2M2Y8AsgTpgAmY7PhCfg=="
+        );
+        assert_eq!(
+            Md5::calculate_digest(b"helloworld").to_base64(),
+            "/F4DjTilcDIIVEHn/nAQsA=="
+        );
+        assert_eq!(
+            Md5::calculate_digest(b"168").to_base64(),
+            "AG9S6RAqjTvi/lYU9CupiQ=="
+        );
+    }
+
+    #[test]
+    fn digest_to_be_bytes_from_helloworld() {
+        assert_eq!(
+            Md5::calculate_digest(b"helloworld").to_be_bytes(),
+            [
+                0xfc, 0x5e, 0x03, 0x8d, 0x38, 0xa5, 0x70, 0x32, 0x08, 0x54, 0x41, 0xe7, 0xfe, 0x70,
+                0x10, 0xb0
+            ]
+        );
+    }
+
+    #[test]
+    fn digest_to_le_bytes_from_helloworld() {
+        assert_eq!(
+            Md5::calculate_digest(b"helloworld").to_le_bytes(),
+            [
+                0xb0, 0x10, 0x70, 0xfe, 0xe7, 0x41, 0x54, 0x08, 0x32, 0x70, 0xa5, 0x38, 0x8d, 0x03,
+                0x5e, 0xfc
+            ]
+        );
+    }
+
+    #[test]
+    fn verify_matching_hex() {
+        assert!(Md5::verify(
+            b"helloworld",
+            "fc5e038d38a57032085441e7fe7010b0"
+        ));
+        assert!(Md5::verify(
+            b"helloworld",
+            "FC5E038D38A57032085441E7FE7010B0"
+        ));
+    }
+
+    #[test]
+    fn verify_non_matching_hex() {
+        assert!(!Md5::verify(b"hello", "fc5e038d38a57032085441e7fe7010b0"));
+        assert!(!Md5::try_verify(b"hello", "fc5e038d38a57032085441e7fe7010b0").unwrap());
+    }
+
+    #[test]
+    fn verify_malformed_hex() {
+        assert!(!Md5::verify(b"helloworld", "fc5e038d"));
+        assert!(matches!(
+            Md5::try_verify(b"helloworld", "fc5e038d"),
+            Err(md5_core::Md5Error::Parse(Md5ParseError::BadLength))
+        ));
+        assert!(matches!(
+            Md5::try_verify(b"helloworld", "zc5e038d38a57032085441e7fe7010b0"),
+            Err(md5_core::Md5Error::Parse(Md5ParseError::BadChar))
+        ));
+    }
+
+    #[test]
+    fn consume_and_verify_against() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"hello");
+        assert!(!md5.verify_against("fc5e038d38a57032085441e7fe7010b0"));
+        md5 = md5.consume(b"world");
+        assert!(md5.verify_against("fc5e038d38a57032085441e7fe7010b0"));
+        assert!(!md5.verify_against("fc5e038d38a57032085441e7fe7010b"));
+    }
+
+    #[test]
+    fn ct_eq_equal_digests() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert!(digest.ct_eq(&Md5::calculate_digest(b"helloworld")));
+    }
+
+    #[test]
+    fn ct_eq_different_digests() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert!(!digest.ct_eq(&Md5::calculate_digest(b"hello")));
+
+        let mut last_byte_differs = *digest.as_bytes();
+        last_byte_differs[15] ^= 0x01;
+        assert!(!digest.ct_eq(&Md5Digest::from(last_byte_differs)));
+    }
+
+    #[test]
+    fn digest_equals_hex_str() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert_eq!(digest, "fc5e038d38a57032085441e7fe7010b0");
+        assert_eq!(digest, "FC5E038D38A57032085441E7FE7010B0");
+        assert_eq!(digest, "fc5e038d38a57032085441E7FE7010B0");
+        assert!(digest == *"fc5e038d38a57032085441e7fe7010b0");
+    }
+
+    #[test]
+    fn digest_not_equals_hex_str() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert_ne!(digest, "d41d8cd98f00b204e9800998ecf8427e");
+        assert_ne!(digest, "fc5e038d38a57032085441e7fe7010b");
+        assert_ne!(digest, "fc5e038d38a57032085441e7fe7010bz");
+    }
+
+    #[test]
+    fn default_and_digest() {
+        assert_eq!(Md5::default().digest(), 0xd41d8cd98f00b204e9800998ecf8427e);
+    }
+
+    #[test]
+    fn clone_partially_consumed_and_branch() {
+        let md5 = Md5::new().consume(b"hello");
+        let world = md5.clone().consume(b"world");
+        let there = md5.clone().consume(b"there");
+
+        assert_eq!(world.digest(), Md5::calculate(b"helloworld"));
+        assert_eq!(there.digest(), Md5::calculate(b"hellothere"));
+        assert_ne!(world.digest(), there.digest());
+        assert_eq!(md5.digest(), Md5::calculate(b"hello"));
+    }
+
+    #[test]
+    fn update_twice_small_and_digest() {
+        let mut md5 = Md5::new();
+        md5.update(b"hello");
+        md5.update(b"world");
+        assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+    }
+
+    #[test]
+    fn update_matches_consume() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.";
+
+        let mut consumed = Md5::new();
+        let mut updated = Md5::new();
+        for _ in 0..5 {
+            consumed = consumed.consume(data);
+            updated.update(data);
+        }
+
+        assert_eq!(updated.digest(), consumed.digest());
+    }
+
+    #[test]
+    fn reset_and_digest_matches_new() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.";
+
+        let mut md5 = Md5::new();
+        md5.update(data);
+        md5.update(b"hello");
+        md5.reset();
+        assert_eq!(md5.digest(), Md5::new().digest());
+
+        md5.update(b"helloworld");
+        assert_eq!(md5.digest(), Md5::new().consume(b"helloworld").digest());
+    }
+
+    #[test]
+    fn finalize_matches_digest() {
+        let mut md5 = Md5::new();
+        md5.update(b"hello");
+        md5.update(b"world");
+        let digest = md5.digest();
+        assert_eq!(md5.finalize().to_u128(), digest);
+    }
+
+    #[test]
+    fn consume_twice_small_and_bytes_processed() {
+        let mut md5 = Md5::new();
+        md5 = md5.consume(b"hello");
+        md5 = md5.consume(b"world");
+        assert_eq!(md5.bytes_processed(), 10);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_several_times_and_digest() {
+        use std::io::Write;
+
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+
+        let mut md5 = Md5::new();
+        assert_eq!(md5.write(&data[..10]).unwrap(), 10);
+        assert_eq!(md5.write(&data[10..70]).unwrap(), 60);
+        md5.write_all(&data[70..]).unwrap();
+        md5.flush().unwrap();
+
+        assert_eq!(md5.digest(), Md5::calculate(data));
+    }
+
+    #[test]
+    fn hasher_finish_is_stable() {
+        use std::hash::Hasher;
+
+        let mut first = Md5::new();
+        first.write(b"helloworld");
+        let mut second = Md5::new();
+        second.write(b"hello");
+        second.write(b"world");
+
+        assert_eq!(first.finish(), 0x085441e7fe7010b0);
+        assert_eq!(first.finish(), first.finish());
+        assert_eq!(first.finish(), second.finish());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader_multi_megabyte() {
+        let data = pattern(3 * 1024 * 1024);
+        let digest = Md5::from_reader(std::io::Cursor::new(&data)).unwrap();
+        assert_eq!(digest.to_u128(), Md5::calculate(&data));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader_with_short_reads() {
+        // returns at most 7 bytes per read, and is interrupted every other read
+        struct ShortReader<'a> {
+            data: &'a [u8],
+            interrupt: bool,
+        }
+
+        impl std::io::Read for ShortReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.interrupt = !self.interrupt;
+                if self.interrupt {
+                    return Err(std::io::ErrorKind::Interrupted.into());
+                }
+
+                let n = buf.len().min(self.data.len()).min(7);
+                buf[..n].copy_from_slice(&self.data[..n]);
+                self.data = &self.data[n..];
+                Ok(n)
+            }
+        }
+
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+        let reader = ShortReader {
+            data,
+            interrupt: false,
+        };
+        assert_eq!(
+            Md5::from_reader(reader).unwrap(),
+            Md5::calculate_digest(data)
+        );
+    }
+
+    // path of a file in the temporary directory that is unique to a test
+    #[cfg(feature = "std")]
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("md5-core-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_file_empty() {
+        let path = temp_path("hash_file_empty");
+        std::fs::write(&path, b"").unwrap();
+        let digest = Md5::hash_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            digest.unwrap().to_u128(),
+            0xd41d8cd98f00b204e9800998ecf8427e
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_file_two_chunks() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.";
+        let path = temp_path("hash_file_two_chunks");
+        std::fs::write(&path, data).unwrap();
+        let digest = Md5::hash_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            digest.unwrap().to_u128(),
+            0xce13701da5de58af48900b63f2da47ca
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader_with_capacity_is_independent_of_chunk_size() {
+        let data = pattern(10000);
+        let one_byte = Md5::from_reader_with_capacity(std::io::Cursor::new(&data), 1).unwrap();
+        let large = Md5::from_reader_with_capacity(std::io::Cursor::new(&data), 1 << 16).unwrap();
+
+        assert_eq!(one_byte, large);
+        assert_eq!(one_byte.to_u128(), Md5::calculate(&data));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader_with_capacity_rejects_zero() {
+        let error = Md5::from_reader_with_capacity(std::io::Cursor::new(b"hello"), 0).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader_with_progress_reports_each_chunk() {
+        let data = pattern(20000);
+        let mut progress = Vec::new();
+        let digest =
+            Md5::from_reader_with_progress(std::io::Cursor::new(&data), |n| progress.push(n));
+
+        assert_eq!(digest.unwrap().to_u128(), Md5::calculate(&data));
+        assert_eq!(progress, vec![8192, 16384, 20000]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader_with_progress_reports_empty_input() {
+        let mut progress = Vec::new();
+        let digest =
+            Md5::from_reader_with_progress(std::io::Cursor::new(b""), |n| progress.push(n));
+
+        assert_eq!(
+            digest.unwrap().to_u128(),
+            0xd41d8cd98f00b204e9800998ecf8427e
+        );
+        assert_eq!(progress, vec![0]);
+    }
+
+    #[test]
+    fn extend_and_digest() {
+        let mut md5 = Md5::new();
+        md5.extend(b"helloworld".iter().copied());
+        assert_eq!(md5.digest(), Md5::calculate(b"helloworld"));
+    }
+
+    #[test]
+    fn extend_by_reference_two_chunks_and_digest() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.";
+
+        let mut md5 = Md5::new();
+        md5.extend(&data[..3]);
+        md5.extend(data[3..].iter());
+        assert_eq!(md5.digest(), Md5::calculate(data));
+    }
+
+    #[test]
+    fn consume_iter_is_independent_of_chunk_boundaries() {
+        let data = &b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis. Nullam consectetur nisi non nibh posuere suscipit. Nam velit est."[..164];
+        let chunks = [&data[..1], &data[1..64], &data[64..]];
+
+        let md5 = Md5::new().consume_iter(chunks);
+        assert_eq!(md5.digest(), Md5::calculate(data));
+
+        let mut md5 = Md5::new();
+        md5.update_iter(data.chunks(7));
+        assert_eq!(md5.digest(), Md5::calculate(data));
+    }
+
+    #[test]
+    fn update_ten_megabytes_in_small_pieces() {
+        let data = pattern(10 * 1024 * 1024);
+
+        let mut md5 = Md5::new();
+        for piece in data.chunks(1024) {
+            md5.update(piece);
+        }
+
+        assert_eq!(md5.digest(), Md5::calculate(&data));
+    }
+
+    #[test]
+    fn update_pieces_across_block_boundaries() {
+        let data = pattern(1000);
+
+        for size in [1, 3, 63, 64, 65, 127, 200] {
+            let mut md5 = Md5::new();
+            for piece in data.chunks(size) {
+                md5.update(piece);
+            }
+            assert_eq!(
+                md5.digest(),
+                Md5::calculate(&data),
+                "pieces of {} bytes",
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn calculate_from_several_megabytes() {
+        let data = pattern(5 * 1024 * 1024 + 17);
+        assert_eq!(Md5::calculate(&data), 0xf727f889617d7b1e70cd77735acc1a6e);
+    }
+
+    #[test]
+    fn calculate_x4_matches_calculate() {
+        let long = pattern(1000);
+        let inputs: [&[u8]; 4] = [b"helloworld", b"", &long, &long[..130]];
+
+        let digests = Md5::calculate_x4(inputs);
+        for (digest, input) in digests.iter().zip(inputs) {
+            assert_eq!(digest, &Md5::calculate_digest(input));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_batch_matches_calculate() {
+        let long = pattern(1000);
+        let inputs: [&[u8]; 3] = [b"", b"helloworld", &long];
+
+        let digests = Md5::calculate_batch(&inputs);
+        assert_eq!(digests.len(), 3);
+        for (digest, input) in digests.iter().zip(inputs) {
+            assert_eq!(digest, &Md5::calculate_digest(input));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_batch_more_than_four_inputs() {
+        let data = pattern(1000);
+        let inputs: Vec<&[u8]> = (0..11).map(|i| &data[..i * 90]).collect();
+
+        let digests = Md5::calculate_batch(&inputs);
+        assert_eq!(digests.len(), inputs.len());
+        for (digest, input) in digests.iter().zip(inputs) {
+            assert_eq!(digest, &Md5::calculate_digest(input));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn calculate_batch_parallel_matches_calculate_batch() {
+        let data = pattern(10000);
+        let inputs: Vec<&[u8]> = (0..500).map(|i| &data[i..i * 17]).collect();
+
+        assert_eq!(
+            Md5::calculate_batch_parallel(&inputs),
+            Md5::calculate_batch(&inputs)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn wipe_populated_state() {
+        let mut md5 = Md5::new();
+        md5.update(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.hello");
+        md5.wipe();
+
+        // nothing is left buffered and every chaining word is 0
+        let (empty_block, _) = Md5::preprocess(b"", 0);
+        assert_eq!(md5.bytes_processed(), 0);
+        assert_eq!(
+            md5.digest(),
+            Md5::calculate_chunks(&empty_block[..64], 0, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn drop_and_finalize_populated_state() {
+        let mut md5 = Md5::new();
+        md5.update(b"hello");
+        drop(md5.clone());
+        assert_eq!(md5.finalize().to_u128(), Md5::calculate(b"hello"));
+    }
+
+    #[test]
+    fn calculate_const_at_compile_time() {
+        const EXPECTED: u128 = Md5::calculate_const(b"helloworld");
+        const _: () = assert!(Md5::calculate_const(b"") == 0xd41d8cd98f00b204e9800998ecf8427e);
+
+        assert_eq!(EXPECTED, 0xfc5e038d38a57032085441e7fe7010b0);
+    }
+
+    #[test]
+    fn calculate_const_matches_calculate() {
+        let data = pattern(300);
+        for len in [0, 1, 55, 56, 64, 100, 128, 184, 300] {
+            assert_eq!(
+                Md5::calculate_const(&data[..len]),
+                Md5::calculate(&data[..len]),
+                "{} bytes",
+                len
+            );
+        }
+        assert_eq!(
+            Md5::calculate_const(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"),
+            0xd174ab98d277d9f5a5611c2c9f419d9f
+        );
+    }
+
+    #[test]
+    fn hmac_rfc2202_test_vectors() {
+        let vectors: [(&[u8], &[u8], u128); 7] = [
+            (&[0x0b; 16], b"Hi There", 0x9294727a3638bb1c13f48ef8158bfc9d),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                0x750c783e6ab0b503eaa86e310a5db738,
+            ),
+            (&[0xaa; 16], &[0xdd; 50], 0x56be34521d144c88dbb8c733f0e8b3f6),
+            (
+                &[
+                    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+                    0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19,
+                ],
+                &[0xcd; 50],
+                0x697eaf0aca3a3aea3a75164746ffaa79,
+            ),
+            (
+                &[0x0c; 16],
+                b"Test With Truncation",
+                0x56461ef2342edc00f9bab995690efd4c,
+            ),
+            (
+                &[0xaa; 80],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                0x6b1ab7fe4bd7bf8f0b62e6ce61b9d0cd,
+            ),
+            (
+                &[0xaa; 80],
+                b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data",
+                0x6f630fad67cda0ee1fb1f562db3aa53e,
+            ),
+        ];
+
+        for (key, data, expected) in vectors {
+            let mut hmac = HmacMd5::new(key);
+            hmac.update(data);
+            assert_eq!(hmac.finalize().to_u128(), expected);
+        }
+    }
+
+    #[test]
+    fn hmac_update_in_pieces() {
+        let mut hmac = HmacMd5::new(b"Jefe");
+        hmac.update(b"what do ya want ");
+        hmac.update(b"for nothing?");
+        assert_eq!(
+            hmac.finalize().to_u128(),
+            0x750c783e6ab0b503eaa86e310a5db738
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5crypt_matches_crypt3() {
+        assert_eq!(
+            md5_core::md5crypt(b"password", "xxxxxxxx"),
+            "$1$xxxxxxxx$UYCIxa628.9qXjpQCjM4a."
+        );
+        assert_eq!(
+            md5_core::md5crypt(b"", "abc"),
+            "$1$abc$Or2rbeUYTvt12aiVzMuS/."
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5crypt_truncates_salt() {
+        assert_eq!(
+            md5_core::md5crypt(b"Hello world!", "saltstring"),
+            "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1"
+        );
+        assert_eq!(
+            md5_core::md5crypt(b"Hello world!", "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1"),
+            "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5crypt_non_ascii_salt() {
+        // same as `openssl passwd -1 -salt é password`
+        let hash = md5_core::md5crypt(b"password", "é");
+        assert_eq!(hash, "$1$é$O0nPWC3X/p5jt3i4/IODm/");
+        assert_eq!(md5_core::md5crypt(b"password", &hash), hash);
+
+        // the 2 bytes of 'é' would be the 8th and 9th, so it is left out whole
+        assert_eq!(
+            md5_core::md5crypt(b"password", "abcdefgé"),
+            "$1$abcdefg$Qp6zr7K0tHxV79N9cCLSc1"
+        );
+    }
+
+    #[test]
+    fn from_state_of_state_round_trip() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.";
+        let md5 = Md5::new().consume(data).consume(data);
+        let (a, b, c, d, length) = md5.state();
+
+        let resumed = Md5::from_state(a, b, c, d, length).consume(b"helloworld");
+        assert_eq!(resumed.digest(), md5.consume(b"helloworld").digest());
+    }
+
+    #[test]
+    fn from_state_length_extension() {
+        let message = b"secretkey:user=guest";
+        let appended = b";admin=true";
+        let known_digest = Md5::calculate_bytes(message);
+
+        // the padding md5 adds to the message: bit '1', zeros, and the length in bits
+        let mut glue = vec![0x80u8];
+        glue.resize(64 - 8 - message.len(), 0);
+        glue.extend_from_slice(&(message.len() as u64 * 8).to_le_bytes());
+
+        let word =
+            |i: usize| u32::from_le_bytes(known_digest[i * 4..i * 4 + 4].try_into().unwrap());
+        let forged = Md5::from_state(word(0), word(1), word(2), word(3), 64).consume(appended);
+
+        let forged_message = [&message[..], &glue, appended].concat();
+        assert_eq!(forged.digest(), Md5::calculate(&forged_message));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serialize_and_resume_mid_stream() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+        let (first, second) = data.split_at(70);
+
+        let json = serde_json::to_string(&Md5::new().consume(first)).unwrap();
+        let resumed: Md5 = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(resumed.consume(second).digest(), Md5::calculate(data));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialize_rejects_full_buffer() {
+        let json = format!(
+            r#"{{"buffer":{:?},"length":64,"a0":0,"b0":0,"c0":0,"d0":0}}"#,
+            [0u8; 64]
+        );
+        assert!(serde_json::from_str::<Md5>(&json).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn save_and_load_state_mid_stream() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+        let (first, second) = data.split_at(70);
+
+        let saved = Md5::new().consume(first).save_state();
+        assert_eq!(saved.len(), 26 + 6);
+        let resumed = Md5::load_state(&saved).unwrap();
+
+        assert_eq!(resumed.bytes_processed(), 70);
+        assert_eq!(resumed.consume(second).digest(), Md5::calculate(data));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn load_state_rejects_malformed_states() {
+        use md5_core::{Md5Error, StateError};
+
+        let saved = Md5::new().consume(b"hello").save_state();
+
+        let mut bad_version = saved.clone();
+        bad_version[0] = 2;
+        assert!(matches!(
+            Md5::load_state(&bad_version),
+            Err(Md5Error::State(StateError::BadVersion))
+        ));
+
+        assert!(matches!(
+            Md5::load_state(&[]),
+            Err(Md5Error::State(StateError::BadLength))
+        ));
+        assert!(matches!(
+            Md5::load_state(&saved[..saved.len() - 1]),
+            Err(Md5Error::State(StateError::BadLength))
+        ));
+        assert!(matches!(
+            Md5::load_state(&[&saved[..], &[0]].concat()),
+            Err(Md5Error::State(StateError::BadLength))
+        ));
+
+        let mut bad_buffer = saved[..26].to_vec();
+        bad_buffer[25] = 64;
+        bad_buffer.extend_from_slice(&[0; 64]);
+        assert!(matches!(
+            Md5::load_state(&bad_buffer),
+            Err(Md5Error::State(StateError::BadBuffer))
+        ));
+    }
+
+    #[test]
+    fn calculate_bits_matches_non_byte_aligned_vectors() {
+        let data = pattern(200);
+
+        assert_eq!(
+            Md5::calculate_bits(&[0x80], 1),
+            0x7e663710ae2348bf0deaca2c79311eae
+        );
+        assert_eq!(
+            Md5::calculate_bits(&[0x7f], 1),
+            0x1da635b1430f171c657206fd69fee0e8
+        );
+        assert_eq!(
+            Md5::calculate_bits(b"helloworld", 75),
+            0x8a00890d03da0d8e34e28aa6cbc59cd1
+        );
+        assert_eq!(
+            Md5::calculate_bits(&data, 5),
+            0xe3f3086499e6b5985c89ff371dd9dbc0
+        );
+        assert_eq!(
+            Md5::calculate_bits(&data, 443),
+            0x36964bb6973c16a6a490cec2d54e027c
+        );
+        assert_eq!(
+            Md5::calculate_bits(&data, 503),
+            0x0c227986816e4f737a2c61f3e650994f
+        );
+        assert_eq!(
+            Md5::calculate_bits(&data, 505),
+            0x06e745b65c341e9032c3ad7a2ed49de1
+        );
+        assert_eq!(
+            Md5::calculate_bits(&data, 1044),
+            0x3e898a29bebaab4bc8698974b30c0081
+        );
+    }
+
+    #[test]
+    fn calculate_bits_with_whole_bytes_matches_calculate() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+        for len in [0, 1, 55, 64, data.len()] {
+            assert_eq!(
+                Md5::calculate_bits(data, len as u64 * 8),
+                Md5::calculate(&data[..len])
+            );
+        }
+    }
+
+    #[test]
+    fn chaining_words_are_independent_of_platform_endianness() {
+        let block: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let md5 = Md5::new().consume(&block);
+
+        // the words after one block, and the digest bytes they map to, are fixed by the
+        // algorithm and must come out the same on little- and big-endian targets
+        assert_eq!(
+            md5.state(),
+            (0x9144d9ca, 0xd901e4c9, 0x72fc5b38, 0x625ff51e, 64)
+        );
+        assert_eq!(
+            Md5::from_state(0x9144d9ca, 0xd901e4c9, 0x72fc5b38, 0x625ff51e, 64).digest(),
+            md5.digest()
+        );
+    }
+
+    #[test]
+    fn preprocess_appends_length_little_endian() {
+        let (block, len) = Md5::preprocess(b"abc", 24);
+        assert_eq!(len, 64);
+        assert_eq!(block[3], 0x80);
+        assert_eq!(block[56..64], [24, 0, 0, 0, 0, 0, 0, 0]);
+
+        let (block, len) = Md5::preprocess(&[0u8; 56], 0x0102030405060708);
+        assert_eq!(len, 128);
+        assert_eq!(block[120..128], [8, 7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn length_in_bits_wraps_modulo_2_64() {
+        let (a, b, c, d, _) = Md5::new().consume(&[b'a'; 64]).state();
+
+        // (2^61 + 66) bytes is 2^64 + 528 bits, so the length field must hold 528
+        let huge = Md5::from_state(a, b, c, d, (1 << 61) + 64).consume(b"hi");
+        let small = Md5::from_state(a, b, c, d, 64).consume(b"hi");
+        assert_eq!(huge.digest(), small.digest());
+
+        let wrapped = Md5::from_state(a, b, c, d, u64::MAX - 63).consume(&[0u8; 64]);
+        assert_eq!(wrapped.bytes_processed(), 0);
+    }
+
+    #[test]
+    fn padding_is_right_for_every_remainder_near_the_block_end() {
+        let expected: [u128; 10] = [
+            0xef1772b6dff9a122358552954ad0df65,
+            0x3b0c8ac703f828b04c6c197006d17218,
+            0x652b906d60af96844ebd21b674f35e93,
+            0xdc2f2f2462a0d72358b2f99389458606,
+            0x762fc2665994b217c52c3c2eb7d9f406,
+            0xcc7ed669cf88f201c3297c6a91e1d18d,
+            0xcced11f7bbbffea2f718903216643648,
+            0x24612f0ce2c9d2cf2b022ef1e027a54f,
+            0xb06521f39153d618550606be297466d5,
+            0x014842d480b571495a4a0363793f7367,
+        ];
+
+        for (len, expected) in (55..=64).zip(expected) {
+            assert_eq!(
+                Md5::calculate(&[b'a'; 64][..len]),
+                expected,
+                "length {}",
+                len
+            );
+
+            let (block, block_len) = Md5::preprocess(&[b'a'; 64][..len % 64], len as u64 * 8);
+            assert_eq!(block_len, if len % 64 < 56 { 64 } else { 128 });
+            assert_eq!(block[len % 64], 0x80);
+            assert_eq!(
+                block[block_len - 8..block_len],
+                (len as u64 * 8).to_le_bytes()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5sums_parses_gnu_format() {
+        let entries = md5_core::Md5Sums::parse(
+            "fc5e038d38a57032085441e7fe7010b0  hello world.txt\n\
+             D41D8CD98F00B204E9800998ECF8427E *empty.bin\n\
+             \n\
+             # comments are skipped\n\
+             \\9dd4e461268c8034f5c8564e155c67a6  a\\\\b\\nc\n",
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].0, Md5::calculate_digest(b"helloworld"));
+        assert_eq!(entries[0].1, "hello world.txt");
+        assert!(!entries[0].2);
+        assert_eq!(entries[1].0, Md5::calculate_digest(b""));
+        assert_eq!(entries[1].1, "empty.bin");
+        assert!(entries[1].2);
+        assert_eq!(entries[2].1, "a\\b\nc");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5sums_parses_bsd_format() {
+        let entries = md5_core::Md5Sums::parse(
+            "MD5 (hello world.txt) = fc5e038d38a57032085441e7fe7010b0\r\n\
+             MD5 (odd (name) = x) = d41d8cd98f00b204e9800998ecf8427e\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, Md5::calculate_digest(b"helloworld"));
+        assert_eq!(entries[0].1, "hello world.txt");
+        assert!(entries[0].2);
+        assert_eq!(entries[1].1, "odd (name) = x");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5sums_rejects_malformed_lines() {
+        use md5_core::{Md5Sums, ParseError};
+
+        assert_eq!(
+            Md5Sums::parse("fc5e038d38a57032085441e7fe7010b0 hello.txt"),
+            Err(ParseError::BadLine(1))
+        );
+        assert_eq!(
+            Md5Sums::parse("\n\nfc5e038d38a57032085441e7fe7010b0  "),
+            Err(ParseError::BadLine(3))
+        );
+        assert_eq!(
+            Md5Sums::parse("zc5e038d38a57032085441e7fe7010b0  hello.txt"),
+            Err(ParseError::BadDigest(1))
+        );
+        assert_eq!(
+            Md5Sums::parse("MD5 (hello.txt) = fc5e038d"),
+            Err(ParseError::BadDigest(1))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5sum_line_matches_coreutils() {
+        let digest = Md5::calculate_digest(b"x");
+        assert_eq!(
+            digest.to_md5sum_line("release notes.txt", false),
+            "9dd4e461268c8034f5c8564e155c67a6  release notes.txt"
+        );
+        assert_eq!(
+            digest.to_md5sum_line("release.tar.gz", true),
+            "9dd4e461268c8034f5c8564e155c67a6 *release.tar.gz"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn md5sum_line_escapes_like_coreutils() {
+        let digest = Md5::calculate_digest(b"x");
+        let line = digest.to_md5sum_line("a\\b\nc\rd", false);
+        assert_eq!(line, "\\9dd4e461268c8034f5c8564e155c67a6  a\\\\b\\nc\\rd");
+
+        let parsed = md5_core::Md5Sums::parse(&line).unwrap();
+        assert_eq!(parsed, [(digest, "a\\b\nc\rd".to_string(), false)]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn copy_hashed_copies_and_hashes() {
+        let data = pattern(20000);
+        let mut copy = Vec::new();
+
+        let (copied, digest) = Md5::copy_hashed(std::io::Cursor::new(&data), &mut copy).unwrap();
+        assert_eq!(copy, data);
+        assert_eq!(copied, 20000);
+        assert_eq!(digest, Md5::calculate_digest(&data));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn copy_hashed_with_partial_writes() {
+        // accepts at most 5 bytes per write
+        struct ShortWriter(Vec<u8>);
+
+        impl std::io::Write for ShortWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                let n = buf.len().min(5);
+                self.0.extend_from_slice(&buf[..n]);
+                Ok(n)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+        let mut writer = ShortWriter(Vec::new());
+        let (copied, digest) = Md5::copy_hashed(&data[..], &mut writer).unwrap();
+
+        assert_eq!(writer.0, data);
+        assert_eq!(copied, data.len() as u64);
+        assert_eq!(digest, Md5::calculate_digest(data));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn verify_file_matching_and_corrupted() {
+        let path = temp_path("verify");
+        std::fs::write(&path, b"helloworld").unwrap();
+        let matching = Md5::verify_file(&path, "fc5e038d38a57032085441e7fe7010b0").unwrap();
+
+        std::fs::write(&path, b"hellow0rld").unwrap();
+        let corrupted = Md5::verify_file(&path, "fc5e038d38a57032085441e7fe7010b0").unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matching);
+        assert!(!corrupted);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn verify_file_errors() {
+        use md5_core::VerifyError;
+
+        let missing = Md5::verify_file(temp_path("missing"), "fc5e038d38a57032085441e7fe7010b0");
+        assert!(
+            matches!(missing, Err(VerifyError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound)
+        );
+
+        let malformed = Md5::verify_file(temp_path("missing"), "not a md5");
+        assert!(matches!(
+            malformed,
+            Err(VerifyError::Parse(Md5ParseError::BadLength))
+        ));
+    }
+
+    #[test]
+    fn calculate_salted_in_both_orders() {
+        assert_eq!(
+            Md5::calculate_salted(b"NaCl", b"password", true),
+            "62d19f7e7ddcb5946728776d25e410ed"
+        );
+        assert_eq!(
+            Md5::calculate_salted(b"NaCl", b"password", false),
+            "f25b019a9470318d44d60e1416631f34"
+        );
+    }
+
+    #[test]
+    fn calculate_iterated_rounds() {
+        let once = Md5::calculate_digest(b"helloworld");
+        assert_eq!(Md5::calculate_iterated(b"helloworld", 1), once);
+        assert_eq!(Md5::calculate_iterated(b"helloworld", 0), once);
+        assert_eq!(
+            Md5::calculate_iterated(b"helloworld", 1000),
+            "35bb8fba0af883b2c5b8cecb05e03402"
+        );
+    }
+
+    #[test]
+    fn compress_single_padded_block_matches_calculate() {
+        let (block, len) = Md5::preprocess(b"message digest", 14 * 8);
+        assert_eq!(len, 64);
+
+        let state = Md5::compress(
+            [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476],
+            block[..64].try_into().unwrap(),
+        );
+        let bytes: [u8; 16] = core::array::from_fn(|i| state[i / 4].to_le_bytes()[i % 4]);
+        assert_eq!(bytes, Md5::calculate_bytes(b"message digest"));
+    }
+
+    #[test]
+    fn with_standard_iv_is_new() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+        let md5 = Md5::with_iv(0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476);
+
+        assert_eq!(md5.state(), Md5::new().state());
+        assert_eq!(md5.consume(data).digest(), Md5::calculate(data));
+    }
+
+    #[test]
+    #[cfg(feature = "digest-traits")]
+    fn digest_trait_chain_update_finalize() {
+        use digest::Digest;
+
+        let output = Digest::finalize(
+            <Md5 as Digest>::new()
+                .chain_update(b"hello")
+                .chain_update(b"world"),
+        );
+        assert_eq!(output.len(), 16);
+        assert_eq!(output[..], Md5::calculate_bytes(b"helloworld"));
+
+        let mut md5 = <Md5 as Digest>::new();
+        Digest::update(&mut md5, b"discarded");
+        Digest::reset(&mut md5);
+        Digest::update(&mut md5, b"abc");
+        assert_eq!(Digest::finalize(md5)[..], Md5::calculate_bytes(b"abc"));
+    }
+
+    #[test]
+    fn truncated_digest_is_a_prefix() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert_eq!(digest.truncated::<4>(), digest.to_be_bytes()[..4]);
+        assert_eq!(digest.truncated::<16>(), digest.to_be_bytes());
+        assert_eq!(digest.truncated::<0>(), [0u8; 0]);
+    }
+
+    #[test]
+    fn fold_u64_of_helloworld() {
+        assert_eq!(
+            Md5::calculate_digest(b"helloworld").fold_u64(),
+            0xf40a426ac6d56082
+        );
+    }
+
+    #[test]
+    fn finalize_with_length_after_streaming() {
+        let data = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas iaculis efficitur magna ac sagittis.";
+        let mut md5 = Md5::new();
+        for chunk in data.chunks(13) {
+            md5.update(chunk);
+        }
+
+        let (digest, length) = md5.finalize_with_length();
+        assert_eq!(length, data.len() as u64);
+        assert_eq!(digest.to_u128(), Md5::calculate(data));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_reader_limited_at_the_boundary() {
+        use md5_core::LimitError;
+        use std::io::Cursor;
+
+        let data = pattern(20000);
+        let expected = Md5::calculate_digest(&data);
+
+        let at = Md5::from_reader_limited(Cursor::new(&data), 20000).unwrap();
+        let under = Md5::from_reader_limited(Cursor::new(&data[..19999]), 20000).unwrap();
+        let over = Md5::from_reader_limited(Cursor::new(&data), 19999);
+
+        assert_eq!(at, expected);
+        assert_eq!(under, Md5::calculate_digest(&data[..19999]));
+        assert!(matches!(over, Err(LimitError::Exceeded)));
+        assert!(matches!(
+            Md5::from_reader_limited(Cursor::new(b"a"), 0),
+            Err(LimitError::Exceeded)
+        ));
+        assert_eq!(
+            Md5::from_reader_limited(Cursor::new(b""), 0).unwrap(),
+            Md5::calculate_digest(b"")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_lines_with_and_without_newlines() {
+        let text = b"first line\nsecond line\r\n\nlast line without newline";
+
+        assert_eq!(
+            Md5::hash_lines(&text[..], true).unwrap(),
+            Md5::calculate_digest(text)
+        );
+        assert_eq!(
+            Md5::hash_lines(&text[..], false).unwrap(),
+            Md5::calculate_digest(b"first linesecond linelast line without newline")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_lines_keeps_a_lone_carriage_return() {
+        assert_eq!(
+            Md5::hash_lines(&b"abc\r"[..], false).unwrap(),
+            Md5::calculate_digest(b"abc\r")
+        );
+        assert_eq!(
+            Md5::hash_lines(&b"a\rb\r\nabc\r"[..], false).unwrap(),
+            Md5::calculate_digest(b"a\rbabc\r")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn digests_sort_in_byte_order() {
+        let mut digests: Vec<Md5Digest> = [&b"a"[..], b"b", b"c", b"helloworld", b""]
+            .iter()
+            .map(|input| Md5::calculate_digest(input))
+            .collect();
+        digests.sort();
+
+        let mut bytes: Vec<[u8; 16]> = digests.iter().map(|d| d.to_be_bytes()).collect();
+        let sorted = bytes.clone();
+        bytes.sort();
+        assert_eq!(bytes, sorted);
+
+        let hex: Vec<String> = digests.iter().map(|d| d.to_string()).collect();
+        assert!(hex.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn digests_dedupe_in_sets() {
+        let digests = [b"a", b"b", b"a"].map(|input| Md5::calculate_digest(input));
+
+        let btree: std::collections::BTreeSet<Md5Digest> = digests.into_iter().collect();
+        let hash: std::collections::HashSet<Md5Digest> = digests.into_iter().collect();
+        assert_eq!(btree.len(), 2);
+        assert_eq!(hash.len(), 2);
+    }
+
+    #[test]
+    fn digest_round_trips_through_u128_and_bytes() {
+        let md5 = Md5::new().consume(b"helloworld");
+
+        assert_eq!(Md5Digest::from(md5.digest()).to_u128(), md5.digest());
+        assert_eq!(u128::from(md5.to_digest()), md5.digest());
+        assert_eq!(<[u8; 16]>::from(md5.to_digest()), md5.digest_bytes());
+        assert_eq!(Md5Digest::from(md5.digest_bytes()), md5.to_digest());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "async")]
+    async fn tokio_copy_into_md5() {
+        let data = pattern(20000);
+        let mut md5 = Md5::new();
+
+        let copied = tokio::io::copy(&mut &data[..], &mut md5).await.unwrap();
+        assert_eq!(copied, 20000);
+        assert_eq!(md5.digest(), Md5::calculate(&data));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "async")]
+    async fn from_async_reader_matches_calculate() {
+        let data = pattern(20000);
+
+        let digest = Md5::from_async_reader(std::io::Cursor::new(&data))
+            .await
+            .unwrap();
+        assert_eq!(digest.to_u128(), Md5::calculate(&data));
+
+        let empty = Md5::from_async_reader(std::io::Cursor::new(b""))
+            .await
+            .unwrap();
+        assert_eq!(empty.to_u128(), Md5::calculate(b""));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_from_and_consume_from_accept_owned_and_borrowed_inputs() {
+        let expected = Md5::calculate(b"helloworld");
+
+        assert_eq!(Md5::calculate_from("helloworld"), expected);
+        assert_eq!(Md5::calculate_from(String::from("helloworld")), expected);
+        assert_eq!(Md5::calculate_from(Vec::from(&b"helloworld"[..])), expected);
+        assert_eq!(
+            Md5::calculate_from(alloc::borrow::Cow::Borrowed(&b"helloworld"[..])),
+            expected
+        );
+
+        let md5 = Md5::new()
+            .consume_from("hel")
+            .consume_from(String::from("lo"))
+            .consume_from(Vec::from(&b"world"[..]));
+        assert_eq!(md5.digest(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn merkle_root_of_one_two_and_three_leaves() {
+        // a single leaf is the plain hash of the data
+        assert_eq!(
+            Md5::merkle_root(b"helloworld", 16),
+            Md5::calculate_digest(b"helloworld")
+        );
+        assert_eq!(Md5::merkle_root(b"", 4), Md5::calculate_digest(b""));
+
+        assert_eq!(
+            Md5::merkle_root(b"helloworld", 5),
+            "065947336a2f2a95ba8899f3675c3be6"
+        );
+
+        // "hell", "owor" and "ld", with the third leaf paired with itself
+        let leaves = [&b"hell"[..], b"owor", b"ld"].map(Md5::calculate_bytes);
+        let left = Md5::calculate_bytes(&[leaves[0], leaves[1]].concat());
+        let right = Md5::calculate_bytes(&[leaves[2], leaves[2]].concat());
+        let root = Md5::merkle_root(b"helloworld", 4);
+        assert_eq!(root, Md5::calculate_digest(&[left, right].concat()));
+        assert_eq!(root, "758452b15d8865a3a774a1b4c201ee85");
+    }
+
+    #[test]
+    fn finalize_into_writes_the_digest_bytes() {
+        let md5 = Md5::new().consume(b"Lorem ipsum dolor sit amet");
+        let expected = md5.to_digest().to_be_bytes();
+
+        let mut out = [0xaa; 16];
+        md5.finalize_into(&mut out);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn builder_with_chunk_size_and_progress() {
+        let data = pattern(1000);
+        let mut progress = Vec::new();
+
+        let digest = md5_core::Md5Builder::new()
+            .chunk_size(300)
+            .progress(|n| progress.push(n))
+            .hash_reader(std::io::Cursor::new(&data))
+            .unwrap();
+
+        assert_eq!(digest, Md5::calculate_digest(&data));
+        assert_eq!(progress, [300, 600, 900, 1000]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn builder_rejects_zero_chunk_size_and_long_inputs() {
+        use md5_core::{LimitError, Md5Builder};
+
+        let zero = Md5Builder::new().chunk_size(0).hash_reader(&b"hello"[..]);
+        assert!(
+            matches!(zero, Err(LimitError::Io(e)) if e.kind() == std::io::ErrorKind::InvalidInput)
+        );
+
+        let limited = Md5Builder::new()
+            .chunk_size(3)
+            .limit(4)
+            .hash_reader(&b"hello"[..]);
+        assert!(matches!(limited, Err(LimitError::Exceeded)));
+    }
 
     #[test]
-    fn consume_twice_small_and_digest() {
+    #[cfg(feature = "std")]
+    fn from_readers_hashes_the_concatenation() {
+        use std::io::Cursor;
+
+        let header = b"Content-Type: text/plain\r\n\r\n".to_vec();
+        let body = pattern(20000);
+
+        let digest = Md5::from_readers([Cursor::new(&header), Cursor::new(&body)]).unwrap();
+        assert_eq!(
+            digest,
+            Md5::calculate_digest(&[&header[..], &body[..]].concat())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_dir_walks_sorted_regular_files() {
+        use std::path::PathBuf;
+
+        let root = temp_path("dir");
+        std::fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        std::fs::write(root.join("b.txt"), b"hello").unwrap();
+        std::fs::write(root.join("a.txt"), b"world").unwrap();
+        std::fs::write(root.join("sub/c.txt"), b"helloworld").unwrap();
+        std::fs::write(root.join("sub/deeper/d.txt"), b"").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.join("a.txt"), root.join("link.txt")).unwrap();
+
+        let digests = Md5::hash_dir(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            digests.unwrap(),
+            [
+                (PathBuf::from("a.txt"), Md5::calculate_digest(b"world")),
+                (PathBuf::from("b.txt"), Md5::calculate_digest(b"hello")),
+                (
+                    PathBuf::from("sub/c.txt"),
+                    Md5::calculate_digest(b"helloworld")
+                ),
+                (
+                    PathBuf::from("sub/deeper/d.txt"),
+                    Md5::calculate_digest(b"")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn verify_reader_matching_and_mismatching() {
+        let data = pattern(20000);
+        let expected = Md5::calculate_digest(&data);
+        let mut corrupted = data.clone();
+        corrupted[10] ^= 1;
+
+        assert!(Md5::verify_reader(std::io::Cursor::new(&data), &expected).unwrap());
+        assert!(!Md5::verify_reader(std::io::Cursor::new(&corrupted), &expected).unwrap());
+        assert!(!Md5::verify_reader(std::io::Cursor::new(&data[..19999]), &expected).unwrap());
+    }
+
+    #[test]
+    fn chaining_words_after_one_block() {
+        let block: [u8; 64] = core::array::from_fn(|i| i as u8);
+        let md5 = Md5::new().consume(&block);
+
+        let iv = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+        assert_eq!(md5.chaining_words(), Md5::compress(iv, &block));
+        assert_eq!(
+            md5.chaining_words(),
+            [0x9144d9ca, 0xd901e4c9, 0x72fc5b38, 0x625ff51e]
+        );
+
+        // buffered bytes don't change them until a block is complete
+        assert_eq!(md5.consume(b"abc").chaining_words(), md5.chaining_words());
+    }
+
+    #[test]
+    fn empty_consumes_at_block_boundaries_are_no_ops() {
+        let data = pattern(200);
+
+        let mut md5 = Md5::new().consume(b"");
+        assert_eq!(md5.state(), Md5::new().state());
+        for chunk in [
+            &data[..63],
+            &data[63..64],
+            &data[64..128],
+            &data[128..130],
+            &data[130..],
+        ] {
+            let empty = md5.consume(b"");
+            assert_eq!(empty.state(), md5.state());
+            assert_eq!(empty.digest(), md5.digest());
+            md5 = empty.consume(chunk).consume(b"");
+        }
+
+        assert_eq!(md5.digest(), Md5::calculate(&data));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hashing_reader_in_odd_sized_chunks() {
+        use std::io::Read;
+
+        let data = pattern(1000);
+        let mut reader = md5_core::HashingReader::new(std::io::Cursor::new(&data));
+
+        let mut read = Vec::new();
+        let mut chunk = [0u8; 7];
+        for size in [1, 7, 3, 5].iter().cycle().take(100) {
+            let n = reader.read(&mut chunk[..*size]).unwrap();
+            read.extend_from_slice(&chunk[..n]);
+        }
+
+        assert_eq!(read, data[..read.len()]);
+        assert_eq!(reader.digest(), Md5::calculate_digest(&read));
+    }
+
+    #[test]
+    fn malformed_inputs_give_the_matching_md5_error() {
+        use md5_core::{Md5Error, StateError};
+
+        fn parse(hex: &str) -> Result<Md5Digest, Md5Error> {
+            Ok(hex.parse()?)
+        }
+
+        assert!(matches!(
+            parse("fc5e"),
+            Err(Md5Error::Parse(Md5ParseError::BadLength))
+        ));
+        assert!(matches!(
+            parse("zc5e038d38a57032085441e7fe7010b0"),
+            Err(Md5Error::Parse(Md5ParseError::BadChar))
+        ));
+        assert!(matches!(
+            Md5::try_verify(b"", "fc5e"),
+            Err(Md5Error::Parse(Md5ParseError::BadLength))
+        ));
+        assert!(matches!(
+            Md5::load_state(&[1, 2, 3]),
+            Err(Md5Error::State(StateError::BadLength))
+        ));
+        assert!(matches!(
+            Md5::try_calculate_bits(b"ab", 17),
+            Err(Md5Error::BitLength)
+        ));
+        assert!(matches!(
+            Md5::try_calculate(b"ab", 1),
+            Err(Md5Error::Length)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn manifest_errors_convert_into_md5_error() {
+        use md5_core::{Md5Error, Md5Sums, ParseError};
+
+        fn count_entries(contents: &str) -> Result<usize, Md5Error> {
+            Ok(Md5Sums::parse(contents)?.len())
+        }
+
+        assert_eq!(
+            count_entries("fc5e038d38a57032085441e7fe7010b0  hello.txt\n").unwrap(),
+            1
+        );
+        assert!(matches!(
+            count_entries("\nnot a md5sum line\n"),
+            Err(Md5Error::Manifest(ParseError::BadLine(2)))
+        ));
+    }
+
+    #[test]
+    fn md5_error_messages() {
+        use md5_core::Md5Error;
+
+        assert_eq!(Md5Error::Length.to_string(), "input is longer than allowed");
+        assert_eq!(
+            Md5Error::BitLength.to_string(),
+            "bit length is greater than the length of the input"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io_failures_give_md5_error_io() {
+        fn verify_missing() -> Result<bool, md5_core::Md5Error> {
+            Ok(Md5::verify_file(
+                temp_path("missing"),
+                "fc5e038d38a57032085441e7fe7010b0",
+            )?)
+        }
+
+        assert!(matches!(
+            verify_missing(),
+            Err(md5_core::Md5Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn limit_errors_convert_into_md5_error() {
+        fn limited(data: &[u8], max_bytes: u64) -> Result<Md5Digest, md5_core::Md5Error> {
+            Ok(Md5::from_reader_limited(data, max_bytes)?)
+        }
+
+        assert!(limited(b"hello", 5).is_ok());
+        assert!(matches!(
+            limited(b"hello", 4),
+            Err(md5_core::Md5Error::Length)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn hashes_bytes_buffers() {
+        let data = pattern(1000);
+        let mut buffer = bytes::Bytes::from(data.clone());
+
+        assert_eq!(Md5::calculate_bytes_crate(&buffer), Md5::calculate(&data));
+
+        let tail = buffer.split_off(300);
+        let md5 = Md5::new().consume_bytes(&buffer).consume_bytes(&tail);
+        assert_eq!(md5.digest(), Md5::calculate(&data));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn etag_of_a_two_part_upload() {
+        // 8 MiB is the part size of the aws cli, this is a 2 part upload
+        let data = pattern(10 * 1024 * 1024 + 100);
+        assert_eq!(
+            Md5::etag(&data, 8 * 1024 * 1024),
+            "ebc7d25fb3b8931a69564567088c663f-2"
+        );
+
+        assert_eq!(
+            Md5::etag(&data[..100], 100),
+            Md5::calculate_hex(&data[..100])
+        );
+        assert_eq!(Md5::etag(b"", 100), Md5::calculate_hex(b""));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_hex_cased() {
+        use md5_core::HexCase;
+
+        assert_eq!(
+            Md5::calculate_hex_cased(b"abc", HexCase::Lower),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            Md5::calculate_hex_cased(b"abc", HexCase::Upper),
+            "900150983CD24FB0D6963F7D28E17F72"
+        );
+        assert_eq!(
+            Md5::calculate_hex_cased(b"abc", HexCase::Lower),
+            Md5::calculate_hex(b"abc")
+        );
+        assert_eq!(
+            Md5::calculate_hex_cased(b"abc", HexCase::Upper),
+            Md5::calculate_hex_upper(b"abc")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn calculate_hex_cased_keeps_leading_zeros() {
+        use md5_core::HexCase;
+
+        // "jk8ssl" hashes to a digest whose first four bytes are zero
+        let lower = Md5::calculate_hex_cased(b"jk8ssl", HexCase::Lower);
+        let upper = Md5::calculate_hex_cased(b"jk8ssl", HexCase::Upper);
+        assert_eq!(lower.len(), 32);
+        assert_eq!(upper.len(), 32);
+        assert_eq!(lower, "0000000018e6137ac2caab16074784a6");
+        assert_eq!(upper, lower.to_uppercase());
+    }
+
+    #[test]
+    fn peek_digest_matches_finalize_on_a_clone() {
         let mut md5 = Md5::new();
-        md5 = md5.consume(b"hello");
-        md5 = md5.consume(b"world");
-        assert_eq!(md5.digest(), 0xfc5e038d38a57032085441e7fe7010b0);
+        for chunk in [&b"hello"[..], &[0x61; 70][..], b"", b"world"] {
+            md5.update(chunk);
+            let first = md5.peek_digest();
+            let second = md5.peek_digest();
+            assert_eq!(first, md5.clone().finalize());
+            assert_eq!(second, md5.clone().finalize());
+        }
+        assert_eq!(
+            md5.finalize(),
+            Md5::new()
+                .consume(b"hello")
+                .consume(&[0x61; 70])
+                .consume(b"world")
+                .finalize()
+        );
     }
 
     #[test]
-    fn consume_twice_two_chunks_and_digest() {
+    fn calculate_rounds_64_is_md5() {
+        for (input, _) in RFC_1321_VECTORS {
+            assert_eq!(Md5::calculate_rounds(input, 64), Md5::calculate(input));
+        }
+        let long = [0x5a; 200];
+        assert_eq!(Md5::calculate_rounds(&long, 64), Md5::calculate(&long));
+    }
+
+    #[test]
+    fn calculate_rounds_0_only_pads() {
+        // with no steps, each block adds the unchanged words to themselves
+        let iv = [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+        let doubled = iv.map(|word| word.wrapping_mul(2));
+        assert_eq!(
+            Md5::calculate_rounds(b"", 0),
+            u128::from_be_bytes(core::array::from_fn(|i| doubled[i / 4].to_le_bytes()[i % 4]))
+        );
+        assert_ne!(Md5::calculate_rounds(b"abc", 63), Md5::calculate(b"abc"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn calculate_rounds_rejects_more_than_64() {
+        Md5::calculate_rounds(b"abc", 65);
+    }
+
+    #[test]
+    fn calculate_with_tables_standard_tables_is_md5() {
+        for (input, _) in RFC_1321_VECTORS {
+            assert_eq!(
+                Md5::calculate_with_tables(input, &Md5::PRECOMPUTED_TABLE, &Md5::SHIFT_TABLE),
+                Md5::calculate(input)
+            );
+        }
+    }
+
+    #[test]
+    fn calculate_with_tables_uses_the_given_tables() {
+        let mut t = Md5::PRECOMPUTED_TABLE;
+        t[63] ^= 1;
+        let mut s = Md5::SHIFT_TABLE;
+        s.swap(0, 1);
+
+        let md5 = Md5::calculate(b"abc");
+        assert_ne!(
+            Md5::calculate_with_tables(b"abc", &t, &Md5::SHIFT_TABLE),
+            md5
+        );
+        assert_ne!(
+            Md5::calculate_with_tables(b"abc", &Md5::PRECOMPUTED_TABLE, &s),
+            md5
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn hash_file_mmap_matches_hash_file() {
+        let data = pattern(100_000);
+        let path = temp_path("hash_file_mmap_matches_hash_file");
+        std::fs::write(&path, &data).unwrap();
+        let mapped = Md5::hash_file_mmap(&path);
+        let streamed = Md5::hash_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mapped.unwrap(), streamed.unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn hash_file_mmap_empty() {
+        let path = temp_path("hash_file_mmap_empty");
+        std::fs::write(&path, b"").unwrap();
+        let digest = Md5::hash_file_mmap(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            digest.unwrap().to_u128(),
+            0xd41d8cd98f00b204e9800998ecf8427e
+        );
+    }
+
+    #[test]
+    fn round_schedule_matches_rfc_1321() {
+        use md5_core::RoundFn;
+
+        // the message word order of the four rounds, as listed in rfc 1321
+        let words: [usize; 64] = [
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, //
+            1, 6, 11, 0, 5, 10, 15, 4, 9, 14, 3, 8, 13, 2, 7, 12, //
+            5, 8, 11, 14, 1, 4, 7, 10, 13, 0, 3, 6, 9, 12, 15, 2, //
+            0, 7, 14, 5, 12, 3, 10, 1, 8, 15, 6, 13, 4, 11, 2, 9,
+        ];
+        let functions = [RoundFn::F, RoundFn::G, RoundFn::H, RoundFn::I];
+
+        let schedule = Md5::round_schedule();
+        for i in 0..64 {
+            assert_eq!(schedule[i], (functions[i / 16], words[i]), "step {}", i);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn borrowed_md5_is_a_writer() {
+        use std::io::Write;
+
+        fn write_twice<W: Write>(mut writer: W) {
+            writer.write_all(b"hello").unwrap();
+            writer.write_all(b"world").unwrap();
+        }
+
         let mut md5 = Md5::new();
-        md5 = md5.consume(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.");
-        md5 = md5.consume(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit aliquam.");
-        assert_eq!(md5.digest(), 0xce13701da5de58af48900b63f2da47ca);
+        write_twice(&mut md5);
+        std::io::copy(&mut &b"!"[..], &mut &mut md5).unwrap();
+        assert_eq!(md5.digest(), Md5::calculate(b"helloworld!"));
+    }
+
+    #[test]
+    fn try_calculate_limit() {
+        use md5_core::Md5Error;
+
+        let data = [0x42; 100];
+        assert_eq!(
+            Md5::try_calculate(&data, 100).unwrap(),
+            Md5::calculate_digest(&data)
+        );
+        assert_eq!(
+            Md5::try_calculate(&data, usize::MAX).unwrap(),
+            Md5::calculate_digest(&data)
+        );
+        assert!(matches!(
+            Md5::try_calculate(&data, 99),
+            Err(Md5Error::Length)
+        ));
+        assert!(matches!(Md5::try_calculate(b"a", 0), Err(Md5Error::Length)));
+        assert!(Md5::try_calculate(b"", 0).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn to_grouped_hex() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert_eq!(
+            digest.to_grouped_hex(2, ':'),
+            "fc:5e:03:8d:38:a5:70:32:08:54:41:e7:fe:70:10:b0"
+        );
+        assert_eq!(
+            digest.to_grouped_hex(4, ':'),
+            "fc5e:038d:38a5:7032:0854:41e7:fe70:10b0"
+        );
+        assert_eq!(
+            digest.to_grouped_hex(8, ':'),
+            "fc5e038d:38a57032:085441e7:fe7010b0"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn to_grouped_hex_remainder_and_whole() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        assert_eq!(
+            digest.to_grouped_hex(5, '-'),
+            "fc5e0-38d38-a5703-20854-41e7f-e7010-b0"
+        );
+        assert_eq!(digest.to_grouped_hex(32, ':'), digest.to_string());
+        assert_eq!(digest.to_grouped_hex(100, ':'), digest.to_string());
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "alloc")]
+    fn to_grouped_hex_rejects_empty_groups() {
+        Md5::calculate_digest(b"").to_grouped_hex(0, ':');
+    }
+
+    #[test]
+    fn sine_table_matches_rfc_1321() {
+        assert_eq!(Md5::sine_table(), Md5::RFC_1321_TABLE);
+        assert_eq!(Md5::PRECOMPUTED_TABLE, Md5::RFC_1321_TABLE);
+        assert_eq!(Md5::PRECOMPUTED_TABLE[0], 0xd76aa478);
+        assert_eq!(Md5::PRECOMPUTED_TABLE[63], 0xeb86d391);
+    }
+
+    #[test]
+    fn pending_bytes() {
+        let mut md5 = Md5::new();
+        assert_eq!(md5.pending_bytes(), 0);
+        md5.update(&[0x61; 100]);
+        assert_eq!(md5.pending_bytes(), 36);
+        md5.update(&[0x61; 28]);
+        assert_eq!(md5.pending_bytes(), 0);
+        md5.update(&[0x61; 63]);
+        assert_eq!(md5.pending_bytes(), 63);
+        assert_eq!(md5.bytes_processed() % 64, md5.pending_bytes() as u64);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_dyn_reader() {
+        let data = [0x33; 20_000];
+        let mut cursor = std::io::Cursor::new(&data[..]);
+        let reader: &mut dyn std::io::Read = &mut cursor;
+
+        assert_eq!(
+            Md5::from_dyn_reader(reader).unwrap().to_u128(),
+            Md5::calculate(&data)
+        );
+    }
+
+    #[test]
+    fn md5_states_compare_equal_whatever_the_chunking() {
+        let data: [u8; 300] = core::array::from_fn(|i| (i % 253) as u8);
+
+        let whole = Md5::new().consume(&data);
+        for size in [1, 7, 63, 64, 65, 200] {
+            let mut pieces = Md5::new();
+            for chunk in data.chunks(size) {
+                pieces.update(chunk);
+            }
+            assert!(pieces == whole, "chunks of {} bytes", size);
+        }
+
+        assert!(whole != Md5::new().consume(&data[..299]));
+        let mut changed = data;
+        changed[299] ^= 1;
+        assert!(whole != Md5::new().consume(&changed));
+        assert!(Md5::new() == Md5::default());
+    }
+
+    #[test]
+    fn md5_state_ignores_stale_buffer_bytes() {
+        // the second object's buffer still holds the bytes of the previous block past the
+        // 4 buffered ones
+        let fresh = Md5::new().consume(&[1; 64]).consume(b"abcd");
+        let reused = Md5::new()
+            .consume(&[1; 60])
+            .consume(&[1; 4])
+            .consume(b"abcd");
+        assert!(fresh == reused);
+
+        let mut reset = Md5::new().consume(&[7; 60]);
+        reset.reset();
+        assert!(reset == Md5::new());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip_keeps_the_state() {
+        let md5 = Md5::new().consume(&[0x5a; 90]);
+        let json = serde_json::to_string(&md5).unwrap();
+
+        assert!(serde_json::from_str::<Md5>(&json).unwrap() == md5);
+    }
+
+    #[test]
+    fn calculate_iter() {
+        assert_eq!(
+            Md5::calculate_iter(b"helloworld".iter().copied()).to_u128(),
+            Md5::calculate(b"helloworld")
+        );
+        assert_eq!(
+            Md5::calculate_iter(core::iter::empty()).to_u128(),
+            Md5::calculate(b"")
+        );
+
+        // several blocks, produced one byte at a time
+        let data: [u8; 200] = core::array::from_fn(|i| (i * 7) as u8);
+        assert_eq!(
+            Md5::calculate_iter((0..200).map(|i| (i * 7) as u8)).to_u128(),
+            Md5::calculate(&data)
+        );
+    }
+
+    #[test]
+    fn write_hex_into_buffer() {
+        let digest = Md5::calculate_digest(b"helloworld");
+        let mut hex = [0u8; 32];
+
+        digest.write_hex(&mut hex);
+        assert_eq!(
+            core::str::from_utf8(&hex).unwrap(),
+            "fc5e038d38a57032085441e7fe7010b0"
+        );
+
+        digest.write_hex_upper(&mut hex);
+        assert_eq!(
+            core::str::from_utf8(&hex).unwrap(),
+            "FC5E038D38A57032085441E7FE7010B0"
+        );
+
+        // leading zero bytes are written too
+        Md5::calculate_digest(b"jk8ssl").write_hex(&mut hex);
+        assert_eq!(
+            core::str::from_utf8(&hex).unwrap(),
+            "0000000018e6137ac2caab16074784a6"
+        );
+    }
+
+    #[test]
+    fn calculate_authenticated_rfc_2202() {
+        assert_eq!(
+            Md5::calculate_authenticated(&[0x0b; 16], b"Hi There").to_u128(),
+            0x9294727a3638bb1c13f48ef8158bfc9d
+        );
+        assert_eq!(
+            Md5::calculate_authenticated(
+                &[0xaa; 80],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )
+            .to_u128(),
+            0x6b1ab7fe4bd7bf8f0b62e6ce61b9d0cd
+        );
+        assert_ne!(
+            Md5::calculate_authenticated(b"key", b"msg"),
+            Md5::calculate_salted(b"key", b"msg", true)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn length_extend_end_to_end() {
+        // a server authenticating requests with md5(secret || query), only the code is public
+        let secret = b"hunter2hunter2";
+        let query = b"user=guest&amount=10";
+        let message = [&secret[..], query].concat();
+        let public_code = Md5::calculate(&message);
+
+        // the attacker only knows the code, the query and the length of the secret
+        let appended = b"&amount=10000";
+        let (forged_code, glue) =
+            Md5::length_extend(public_code, (secret.len() + query.len()) as u64, appended);
+
+        let forged_message = [&message[..], &glue, appended].concat();
+        assert_eq!(forged_code, Md5::calculate(&forged_message));
+        assert_eq!(forged_message.len(), 64 + appended.len());
+        assert_eq!(glue[0], 0x80);
+        assert_eq!(glue[glue.len() - 8..], 272u64.to_le_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn length_extend_lengths_near_the_u64_limit() {
+        let known = Md5::calculate_bytes(b"abc");
+        let word = |i: usize| u32::from_le_bytes(known[i * 4..i * 4 + 4].try_into().unwrap());
+
+        for original_len in [u64::MAX - 9, u64::MAX - 8, u64::MAX - 1, u64::MAX] {
+            let (forged, glue) =
+                Md5::length_extend(u128::from_be_bytes(known), original_len, b"extra");
+
+            let total = original_len.wrapping_add(glue.len() as u64);
+            assert_eq!(total % 64, 0, "length {}", original_len);
+            assert_eq!(
+                glue[glue.len() - 8..],
+                original_len.wrapping_mul(8).to_le_bytes()
+            );
+            assert_eq!(
+                forged,
+                Md5::from_state(word(0), word(1), word(2), word(3), total)
+                    .consume(b"extra")
+                    .digest()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn length_extend_glue_lengths() {
+        for original_len in [0usize, 1, 55, 56, 63, 64, 65, 119, 120, 200] {
+            let original = vec![0x42; original_len];
+            let (forged, glue) =
+                Md5::length_extend(Md5::calculate(&original), original_len as u64, b"extra");
+
+            // the glue completes the original message to whole blocks
+            assert_eq!(
+                (original_len + glue.len()) % 64,
+                0,
+                "length {}",
+                original_len
+            );
+            assert!((9..=72).contains(&glue.len()), "length {}", original_len);
+            assert_eq!(
+                forged,
+                Md5::calculate(&[&original[..], &glue, b"extra"].concat()),
+                "length {}",
+                original_len
+            );
+        }
     }
 }
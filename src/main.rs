@@ -0,0 +1,110 @@
+//! A small `md5sum` clone, built with the `cli` feature
+//!
+//! `md5sum [FILE]...` prints the `hash  filename` line of every file, reading stdin when no
+//! file (or `-`) is given. `md5sum -c FILE` checks the files listed in a checksum file and
+//! exits with an error if any of them doesn't match.
+
+use md5_core::md5_core::{Md5, Md5Digest, Md5Sums};
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let ok = match args.first().map(String::as_str) {
+        Some("-c") | Some("--check") => match &args[1..] {
+            [manifest] => check(manifest),
+            _ => {
+                eprintln!("usage: md5sum -c FILE");
+                return ExitCode::from(2);
+            }
+        },
+        _ if args.is_empty() => print_hashes(&[String::from("-")]),
+        _ => print_hashes(&args),
+    };
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn hash(filename: &str) -> io::Result<Md5Digest> {
+    if filename == "-" {
+        Md5::from_reader(io::stdin().lock())
+    } else {
+        Md5::hash_file(filename)
+    }
+}
+
+fn print_hashes(filenames: &[String]) -> bool {
+    let mut ok = true;
+    for filename in filenames {
+        match hash(filename) {
+            Ok(digest) => println!("{}", digest.to_md5sum_line(filename, false)),
+            Err(error) => {
+                eprintln!("md5sum: {}: {}", filename, error);
+                ok = false;
+            }
+        }
+    }
+
+    ok
+}
+
+fn check(manifest: &str) -> bool {
+    let contents = if manifest == "-" {
+        let mut contents = String::new();
+        io::stdin().read_to_string(&mut contents).map(|_| contents)
+    } else {
+        fs::read_to_string(manifest)
+    };
+    let entries = match contents {
+        Ok(contents) => match Md5Sums::parse(&contents) {
+            Ok(entries) => entries,
+            Err(error) => {
+                eprintln!("md5sum: {}: {}", manifest, error);
+                return false;
+            }
+        },
+        Err(error) => {
+            eprintln!("md5sum: {}: {}", manifest, error);
+            return false;
+        }
+    };
+
+    let mut mismatched = 0;
+    let mut unreadable = 0;
+    for (expected, filename, _) in entries {
+        match hash(&filename) {
+            Ok(digest) if digest == expected => println!("{}: OK", filename),
+            Ok(_) => {
+                println!("{}: FAILED", filename);
+                mismatched += 1;
+            }
+            Err(error) => {
+                eprintln!("md5sum: {}: {}", filename, error);
+                println!("{}: FAILED open or read", filename);
+                unreadable += 1;
+            }
+        }
+    }
+
+    if unreadable > 0 {
+        eprintln!(
+            "md5sum: WARNING: {} listed file(s) could not be read",
+            unreadable
+        );
+    }
+    if mismatched > 0 {
+        eprintln!(
+            "md5sum: WARNING: {} computed checksum(s) did NOT match",
+            mismatched
+        );
+    }
+
+    mismatched == 0 && unreadable == 0
+}
@@ -0,0 +1,48 @@
+//! Bindings for javascript, built with the `wasm` feature
+//!
+//! ```js
+//! import { WasmMd5, md5Hex } from "md5-core";
+//!
+//! const md5 = new WasmMd5();
+//! md5.update(new Uint8Array([104, 101, 108, 108, 111]));
+//! md5.update(new TextEncoder().encode("world"));
+//! md5.digestHex(); // "fc5e038d38a57032085441e7fe7010b0"
+//! ```
+
+use crate::md5_core::Md5;
+use alloc::string::String;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Incremental md5 calculation, so big files can be hashed one `Uint8Array` chunk at a time
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct WasmMd5 {
+    md5: Md5,
+}
+
+#[wasm_bindgen]
+impl WasmMd5 {
+    /// Starts a new md5 calculation
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self { md5: Md5::new() }
+    }
+
+    /// Feeds a chunk of data to the md5 calculation
+    pub fn update(&mut self, data: &[u8]) {
+        self.md5.update(data);
+    }
+
+    /// Returns the md5 hash of all the chunks fed so far as lowercase hex. More chunks can
+    /// still be fed afterwards
+    #[wasm_bindgen(js_name = digestHex)]
+    pub fn digest_hex(&self) -> String {
+        self.md5.digest_hex()
+    }
+}
+
+/// Returns the md5 hash of the data as lowercase hex
+#[wasm_bindgen(js_name = md5Hex)]
+pub fn md5_hex(data: &[u8]) -> String {
+    Md5::calculate_hex(data)
+}
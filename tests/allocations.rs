@@ -0,0 +1,83 @@
+use md5_core::md5_core::Md5;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// counts the allocations of each thread, so the other tests running in parallel don't interfere
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // try_with, since the thread local may already be destroyed when a thread exits
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+// `len` bytes of test data. 251 is prime, so the pattern doesn't repeat with the 64 byte blocks
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn consume_does_not_allocate() {
+    let data = pattern(10_000);
+    let expected = Md5::calculate(&data);
+
+    let before = allocations();
+    let mut md5 = Md5::new();
+    for piece in data.chunks(3) {
+        md5 = md5.consume(piece);
+    }
+    let digest = md5.digest();
+    let after = allocations();
+
+    assert_eq!(digest, expected);
+    assert_eq!(after - before, 0);
+}
+
+#[test]
+fn update_does_not_allocate() {
+    let data = [0x61u8; 1000];
+    let expected = Md5::calculate(&data);
+
+    let before = allocations();
+    let mut md5 = Md5::new();
+    for byte in data.chunks(1) {
+        md5.update(byte);
+    }
+    let digest = md5.finalize();
+    let after = allocations();
+
+    assert_eq!(digest.to_u128(), expected);
+    assert_eq!(after - before, 0);
+}
+
+#[test]
+fn update_in_kibibyte_pieces_does_not_allocate() {
+    let data = pattern(10 * 1024 * 1024);
+
+    let before = allocations();
+    let mut md5 = Md5::new();
+    for piece in data.chunks(1024) {
+        md5.update(piece);
+    }
+    let after = allocations();
+
+    assert_eq!(after - before, 0);
+    assert_eq!(md5.digest(), Md5::calculate(&data));
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("md5-core-cli-{}-{}", std::process::id(), name))
+}
+
+fn md5sum() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_md5sum"))
+}
+
+#[test]
+fn prints_md5sum_lines() {
+    let path = temp_path("hello");
+    fs::write(&path, b"helloworld").unwrap();
+
+    let output = md5sum().arg(&path).output().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("fc5e038d38a57032085441e7fe7010b0  {}\n", path.display())
+    );
+}
+
+#[test]
+fn check_mode_fails_on_mismatch() {
+    let path = temp_path("check");
+    fs::write(&path, b"helloworld").unwrap();
+    let good = temp_path("good.md5");
+    fs::write(
+        &good,
+        format!("fc5e038d38a57032085441e7fe7010b0  {}\n", path.display()),
+    )
+    .unwrap();
+    let bad = temp_path("bad.md5");
+    fs::write(
+        &bad,
+        format!("d41d8cd98f00b204e9800998ecf8427e  {}\n", path.display()),
+    )
+    .unwrap();
+
+    let passed = md5sum().arg("-c").arg(&good).output().unwrap();
+    let failed = md5sum().arg("-c").arg(&bad).output().unwrap();
+    for file in [&path, &good, &bad] {
+        fs::remove_file(file).unwrap();
+    }
+
+    assert!(passed.status.success());
+    assert_eq!(
+        String::from_utf8(passed.stdout).unwrap(),
+        format!("{}: OK\n", path.display())
+    );
+    assert!(!failed.status.success());
+    assert_eq!(
+        String::from_utf8(failed.stdout).unwrap(),
+        format!("{}: FAILED\n", path.display())
+    );
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "wasm")]
+
+use md5_core::wasm::{md5_hex, WasmMd5};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test as test;
+
+#[test]
+fn md5_hex_of_known_input() {
+    assert_eq!(md5_hex(b"helloworld"), "fc5e038d38a57032085441e7fe7010b0");
+}
+
+#[test]
+fn wasm_md5_hashes_chunks() {
+    let mut md5 = WasmMd5::new();
+    for chunk in b"Lorem ipsum dolor sit amet, consectetur adipiscing elit".chunks(7) {
+        md5.update(chunk);
+    }
+    assert_eq!(md5.digest_hex(), "fc10a08df7fafa3871166646609e1c95");
+}